@@ -0,0 +1,37 @@
+use autogitignore::app::App;
+use autogitignore::cli::Args;
+use autogitignore::config::Config;
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+/// Roughly the size of the gitignore.io template list.
+const TEMPLATE_COUNT: usize = 1000;
+
+fn app_with_templates() -> App {
+    let mut app = App::new(&Args::default(), Config::default());
+    let stems = ["Node", "Python", "Rust", "Go", "Java", "Kotlin", "Django", "Unity", "VisualStudio", "macOS"];
+    let templates = (0..TEMPLATE_COUNT)
+        .map(|i| format!("{}{}", stems[i % stems.len()], i / stems.len()))
+        .collect();
+    app.set_templates(templates);
+    app
+}
+
+/// Filters the full list the way a keystroke does, for an empty query and a few typical ones.
+fn bench_apply_filter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("apply_filter");
+    for query in ["", "py", "go", "visual"] {
+        let mut app = app_with_templates();
+        app.search_query = query.to_string();
+        group.bench_function(if query.is_empty() { "empty" } else { query }, |b| {
+            b.iter(|| {
+                app.apply_filter();
+                black_box(app.filtered_templates.len())
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_apply_filter);
+criterion_main!(benches);
@@ -0,0 +1,53 @@
+/// Common short names users type for templates, keyed by lowercase template name.
+const ALIASES: &[(&str, &[&str])] = &[
+    ("python", &["py", "python3"]),
+    ("node", &["js", "nodejs", "npm", "javascript"]),
+    ("typescript", &["ts"]),
+    ("kubernetes", &["k8s"]),
+    ("go", &["golang"]),
+    ("rust", &["rs", "cargo"]),
+    ("ruby", &["rb", "gem"]),
+    ("csharp", &["cs", "c#"]),
+    ("c++", &["cpp", "cplusplus"]),
+    ("java", &["jvm"]),
+    ("kotlin", &["kt"]),
+    ("terraform", &["tf"]),
+    ("dotnetcore", &["dotnet"]),
+    ("visualstudiocode", &["vscode", "code"]),
+    ("visualstudio", &["vs"]),
+    ("jetbrains", &["idea", "intellij"]),
+    ("macos", &["mac", "osx"]),
+    ("windows", &["win"]),
+    ("linux", &["unix"]),
+    ("vim", &["vi", "nvim"]),
+    ("emacs", &["elisp"]),
+];
+
+/// Aliases registered for `template` (case-insensitive); empty when there are none.
+pub fn aliases_for(template: &str) -> &'static [&'static str] {
+    ALIASES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(template))
+        .map(|(_, aliases)| *aliases)
+        .unwrap_or(&[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aliases_are_found_case_insensitively() {
+        assert_eq!(aliases_for("Kubernetes"), ["k8s"]);
+        assert_eq!(aliases_for("VisualStudioCode"), ["vscode", "code"]);
+        assert!(aliases_for("Haskell").is_empty());
+    }
+
+    #[test]
+    fn aliases_are_lowercase() {
+        // Search compares them against the lowercased query without lowercasing them again.
+        for (_, aliases) in ALIASES {
+            assert!(aliases.iter().all(|alias| *alias == alias.to_lowercase()), "{aliases:?}");
+        }
+    }
+}
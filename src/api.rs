@@ -1,16 +1,60 @@
-use anyhow::Result;
-use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+use sha2::{Digest, Sha256};
 
 use std::fs;
 use std::path::PathBuf;
-use directories::ProjectDirs;
 
+use crate::config::project_dirs;
 use crate::models::CacheData;
 
+/// Base URL of the gitignore.io (Toptal) API.
+const API_BASE: &str = "https://www.toptal.com/developers/gitignore/api";
+/// Display name of the template source, shown in the TUI header.
+pub const PROVIDER_NAME: &str = "gitignore.io";
+/// Maximum number of per-template requests in flight at once.
+const CONTENT_CONCURRENCY: usize = 4;
+/// Largest template content accepted, from the API list or from an arbitrary URL.
+const MAX_TEMPLATE_BYTES: usize = 1024 * 1024;
+/// Environment variable whose value is sent as a bearer token unless an `Authorization` header is set.
+pub const AUTH_TOKEN_ENV: &str = "AUTOGITIGNORE_AUTH_TOKEN";
+/// How long the reachability check waits for the API before giving up.
+const REACHABILITY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
 /// Responsible for all external API communication and local caching.
 pub struct ApiClient {
     client: reqwest::Client,
     cache_path: PathBuf,
+    /// Optional file that receives the raw list response before parsing (debugging aid).
+    raw_dump_path: Option<PathBuf>,
+    /// Maximum number of template contents kept in the cache file; least recently used go first.
+    content_limit: Option<usize>,
+}
+
+/// Location of the template cache file; resolved without creating anything on disk.
+pub fn cache_file_path() -> Result<PathBuf> {
+    Ok(project_dirs()?.cache_dir().join("cache.json"))
+}
+
+/// Splits a `Key: Value` header argument, validating both parts.
+pub fn parse_header(header: &str) -> Result<(String, String)> {
+    let (name, value) = header
+        .split_once(':')
+        .ok_or_else(|| anyhow::anyhow!("expected \"Key: Value\", got {:?}", header))?;
+    let (name, value) = (name.trim(), value.trim());
+    parse_header_parts(name, value)?;
+    Ok((name.to_string(), value.to_string()))
+}
+
+/// Converts a header name and value into their HTTP types, rejecting invalid characters.
+fn parse_header_parts(name: &str, value: &str) -> Result<(HeaderName, HeaderValue)> {
+    let header_name =
+        HeaderName::from_bytes(name.as_bytes()).map_err(|_| anyhow::anyhow!("invalid header name {:?}", name))?;
+    let mut header_value =
+        HeaderValue::from_str(value).map_err(|_| anyhow::anyhow!("invalid value for header {}", name))?;
+    header_value.set_sensitive(header_name == AUTHORIZATION);
+    Ok((header_name, header_value))
 }
 
 /// Helper struct for deserializing Toptal's template JSON format.
@@ -22,65 +66,614 @@ struct ToptalTemplate {
 
 impl ApiClient {
     /// Initializes a new ApiClient, creating the necessary local cache directories.
-    pub fn new() -> Result<Self> {
+    /// `extra_headers` are sent with every request and may replace the default User-Agent.
+    pub fn new(extra_headers: &[(String, String)]) -> Result<Self> {
         let mut headers = HeaderMap::new();
         headers.insert(USER_AGENT, HeaderValue::from_static("autogitignore-tui"));
+        for (name, value) in extra_headers {
+            let (name, value) = parse_header_parts(name, value)?;
+            headers.insert(name, value);
+        }
+        if !headers.contains_key(AUTHORIZATION)
+            && let Some(token) = std::env::var(AUTH_TOKEN_ENV).ok().filter(|token| !token.is_empty())
+        {
+            let mut value = HeaderValue::from_str(&format!("Bearer {}", token))
+                .map_err(|_| anyhow::anyhow!("{} contains characters not allowed in a header", AUTH_TOKEN_ENV))?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
 
         let client = reqwest::Client::builder()
             .default_headers(headers)
             .build()?;
 
-        let proj_dirs = ProjectDirs::from("com", "autogitignore", "autogitignore")
-            .ok_or_else(|| anyhow::anyhow!("Failed to determine cache directory"))?;
-        let cache_dir = proj_dirs.cache_dir().to_path_buf();
-        fs::create_dir_all(&cache_dir)?;
-        let cache_path = cache_dir.join("cache.json");
+        let cache_path = cache_file_path()?;
+        if let Some(cache_dir) = cache_path.parent() {
+            fs::create_dir_all(cache_dir)?;
+        }
+
+        Ok(Self {
+            client,
+            cache_path,
+            raw_dump_path: None,
+            content_limit: None,
+        })
+    }
+
+    /// Writes the raw JSON body of every full sync to `path` before it is parsed.
+    pub fn with_raw_dump(mut self, path: Option<PathBuf>) -> Self {
+        self.raw_dump_path = path;
+        self
+    }
 
-        Ok(Self { client, cache_path })
+    /// Caps the number of cached template contents (the template list itself is always kept).
+    pub fn with_content_limit(mut self, limit: Option<usize>) -> Self {
+        self.content_limit = limit;
+        self
+    }
+
+    /// Sidecar file holding the SHA-256 of the serialized cache.
+    fn cache_hash_path(&self) -> PathBuf {
+        self.cache_path.with_extension("json.sha256")
     }
 
     /// Attempts to load the template data from the local cache file.
+    /// A cache whose contents don't match the stored hash is treated as missing. Caches written
+    /// before hashes were stored have no hash file and are read leniently instead; if one was cut
+    /// off mid-write, its complete entries are kept.
+    /// Fields are parsed independently, so a partially readable cache still yields the template
+    /// list; missing contents are fetched lazily on selection.
     pub fn load_cache(&self) -> Option<CacheData> {
         if !self.cache_path.exists() {
             return None;
         }
         let content = fs::read_to_string(&self.cache_path).ok()?;
-        serde_json::from_str(&content).ok()
+        if let Ok(expected) = fs::read_to_string(self.cache_hash_path())
+            && expected.trim() != content_hash(&content)
+        {
+            return None;
+        }
+        parse_cache_text(&content)
     }
 
-    /// Persists the provided CacheData to the local file system.
+    /// Persists the provided CacheData to the local file system, along with its hash.
+    /// With a content limit, the least recently used contents beyond it are left out.
     pub fn save_cache(&self, data: &CacheData) -> Result<()> {
-        let content = serde_json::to_string(data)?;
-        fs::write(&self.cache_path, content)?;
+        let content = match self.content_limit {
+            Some(limit) if data.contents.len() > limit => serde_json::to_string(&evict_lru(data, limit))?,
+            _ => serde_json::to_string(data)?,
+        };
+        fs::write(&self.cache_path, &content)
+            .with_context(|| format!("Failed to write {}", self.cache_path.display()))?;
+        let hash_path = self.cache_hash_path();
+        fs::write(&hash_path, content_hash(&content))
+            .with_context(|| format!("Failed to write {}", hash_path.display()))?;
         Ok(())
     }
 
+    /// Merges freshly fetched contents into the cache file and marks them as recently used,
+    /// so evicted templates are cached again once re-fetched.
+    pub fn store_contents(&self, contents: &std::collections::HashMap<String, String>) -> Result<()> {
+        if contents.is_empty() {
+            return Ok(());
+        }
+        let Some(mut cache) = self.load_cache() else {
+            return Ok(());
+        };
+        let now = unix_now();
+        for (name, content) in contents {
+            cache.contents.insert(name.clone(), content.clone());
+            cache.last_used.insert(name.clone(), now);
+        }
+        self.save_cache(&cache)
+    }
+
+    /// Marks `names` as recently used so they are the last to be evicted.
+    pub fn record_use(&self, names: &[String]) -> Result<()> {
+        let Some(mut cache) = self.load_cache() else {
+            return Ok(());
+        };
+        let now = unix_now();
+        for name in names {
+            cache.last_used.insert(name.clone(), now);
+        }
+        self.save_cache(&cache)
+    }
+
+    /// Removes the cache file and its sidecars. Missing files are not an error.
+    pub fn clear_cache(&self) -> Result<()> {
+        for path in [self.cache_path.clone(), self.cache_hash_path()] {
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Location of the cache file.
+    pub fn cache_path(&self) -> &std::path::Path {
+        &self.cache_path
+    }
+
     /// Fetches the latest list of templates and their contents from gitignore.io (Toptal).
     pub async fn fetch_all_data(&self) -> Result<CacheData> {
-        let url = "https://www.toptal.com/developers/gitignore/api/list?format=json";
-        let response = self.client.get(url).send().await?;
-        
-        let status = response.status();
-        if !status.is_success() {
-            return Err(anyhow::anyhow!("Toptal API error: {}", status));
+        let url = format!("{}/list?format=json", API_BASE);
+        let body = self.get_text(&url).await?;
+        if let Some(path) = &self.raw_dump_path {
+            fs::write(path, &body).with_context(|| format!("Failed to write {}", path.display()))?;
         }
+        let mut data = parse_template_list(&body)?;
+        // Keep usage history across syncs so LRU eviction stays meaningful.
+        data.last_used = self.load_cache().map(|cache| cache.last_used).unwrap_or_default();
+        Ok(data)
+    }
+
+    /// Fetches the contents of a single template by its API slug (e.g. `visualstudiocode`).
+    pub async fn fetch_template(&self, slug: &str) -> Result<String> {
+        let url = format!("{}/{}", API_BASE, slug);
+        self.get_text(&url).await
+    }
 
-        let data: std::collections::HashMap<String, ToptalTemplate> = response.json().await?;
-        
-        let mut templates = Vec::new();
-        let mut contents = std::collections::HashMap::new();
+    /// Fetches a gitignore from an arbitrary URL, returning a template name derived from its path
+    /// (e.g. `my` for `.../my.gitignore`) and the content. Only reasonably sized plain text is accepted.
+    pub async fn fetch_url_template(&self, url: &str) -> Result<(String, String)> {
+        let parsed = reqwest::Url::parse(url).map_err(|e| anyhow::anyhow!("Invalid URL {}: {}", url, e))?;
+        let name = url_template_name(&parsed);
 
-        for (_key, val) in data {
-            templates.push(val.name.clone());
-            contents.insert(val.name, val.contents);
+        let response = self
+            .client
+            .get(parsed)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| {
+                let message = describe_request_error(&e);
+                anyhow::Error::new(e).context(message)
+            })?;
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("text/plain")
+            .to_ascii_lowercase();
+        if content_type.starts_with("text/html") {
+            return Err(anyhow::anyhow!(
+                "{} returned an HTML page, not a gitignore file (use the raw file URL)",
+                url
+            ));
+        }
+        if !content_type.starts_with("text/") && !content_type.starts_with("application/octet-stream") {
+            return Err(anyhow::anyhow!("{} returned {}, expected plain text", url, content_type));
+        }
+        if response.content_length().is_some_and(|len| len as usize > MAX_TEMPLATE_BYTES) {
+            return Err(anyhow::anyhow!("{} is larger than {} KiB", url, MAX_TEMPLATE_BYTES / 1024));
         }
 
-        templates.sort();
+        let bytes = response.bytes().await.map_err(|e| {
+            let message = describe_request_error(&e);
+            anyhow::Error::new(e).context(message)
+        })?;
+        if bytes.len() > MAX_TEMPLATE_BYTES {
+            return Err(anyhow::anyhow!("{} is larger than {} KiB", url, MAX_TEMPLATE_BYTES / 1024));
+        }
+        let content = String::from_utf8(bytes.to_vec())
+            .map_err(|_| anyhow::anyhow!("{} is not valid UTF-8 text", url))?;
+        Ok((name, content))
+    }
+
+    /// Sends a lightweight HEAD request to check that the API is reachable.
+    pub async fn check_reachable(&self) -> Result<()> {
+        self.client
+            .head(format!("{}/list", API_BASE))
+            .timeout(REACHABILITY_TIMEOUT)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(|e| {
+                let message = describe_request_error(&e);
+                anyhow::Error::new(e).context(message)
+            })
+    }
 
-        Ok(CacheData {
-            templates,
-            contents,
+    /// Performs a GET request and returns the body, mapping failures to actionable messages.
+    /// The underlying `reqwest::Error` stays in the error chain.
+    async fn get_text(&self, url: &str) -> Result<String> {
+        let result = async {
+            self.client
+                .get(url)
+                .send()
+                .await?
+                .error_for_status()?
+                .text()
+                .await
+        }
+        .await;
+
+        result.map_err(|e| {
+            let message = describe_request_error(&e);
+            anyhow::Error::new(e).context(message)
         })
     }
 
+    /// Fetches several `(name, slug)` templates concurrently, with at most `CONTENT_CONCURRENCY`
+    /// requests in flight. Each name carries its own result so a single failure doesn't abort the batch.
+    /// Results are sorted by name, independent of completion order.
+    pub async fn fetch_templates(&self, targets: &[(String, String)]) -> Vec<(String, Result<String>)> {
+        let mut results: Vec<(String, Result<String>)> = stream::iter(targets.iter().cloned())
+            .map(|(name, slug)| async move {
+                let result = self.fetch_template(&slug).await;
+                (name, result)
+            })
+            .buffer_unordered(CONTENT_CONCURRENCY)
+            .collect()
+            .await;
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+        results
+    }
+}
+
+/// Parses cache text leniently. A document cut off mid-write is first closed at its last
+/// complete entry, so whatever was written in full is kept.
+fn parse_cache_text(content: &str) -> Option<CacheData> {
+    let value: serde_json::Value = serde_json::from_str(content)
+        .ok()
+        .or_else(|| serde_json::from_str(&close_truncated(content)?).ok())?;
+    parse_cache_lenient(&value)
+}
+
+/// Closes a JSON document that was cut off (e.g. by an interrupted write) after its last
+/// complete array element or object member, or `None` if it has no such point.
+fn close_truncated(text: &str) -> Option<String> {
+    let mut open: Vec<u8> = Vec::new();
+    let mut cut = None;
+    let (mut in_string, mut escaped) = (false, false);
+    // Scanning bytes is safe: the structural characters never occur inside multi-byte UTF-8.
+    for (i, byte) in text.bytes().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' => open.push(b'}'),
+            b'[' => open.push(b']'),
+            b'}' | b']' => {
+                open.pop();
+                if !open.is_empty() {
+                    cut = Some((i + 1, open.clone()));
+                }
+            }
+            b',' => cut = Some((i, open.clone())),
+            _ => {}
+        }
+    }
+    let (end, open) = cut?;
+    let mut closed = text[..end].to_string();
+    closed.extend(open.iter().rev().map(|&b| b as char));
+    Some(closed)
+}
+
+/// Builds `CacheData` from whatever parts of a cache document are readable.
+/// Non-string entries are skipped; templates fall back to the content keys if the list is unusable.
+fn parse_cache_lenient(value: &serde_json::Value) -> Option<CacheData> {
+    let contents: std::collections::HashMap<String, String> = value
+        .get("contents")
+        .and_then(|v| v.as_object())
+        .map(|map| {
+            map.iter()
+                .filter_map(|(name, text)| Some((name.clone(), text.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut templates: Vec<String> = value
+        .get("templates")
+        .and_then(|v| v.as_array())
+        .map(|list| list.iter().filter_map(|t| t.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+    if templates.is_empty() {
+        templates = contents.keys().cloned().collect();
+        templates.sort();
+    }
+
+    if templates.is_empty() {
+        return None;
+    }
+    let last_used = value
+        .get("last_used")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default();
+    let slugs = value
+        .get("slugs")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default();
+    Some(CacheData {
+        templates,
+        contents,
+        last_used,
+        slugs,
+        oversized: Vec::new(),
+    })
+}
+
+/// Whether a `--gen` entry should be fetched as a URL rather than resolved as a template name.
+pub fn is_template_url(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    lower.starts_with("https://") || lower.starts_with("http://")
+}
+
+/// Template name for a URL: its last path segment without a `.gitignore` extension, or the host.
+fn url_template_name(url: &reqwest::Url) -> String {
+    let segment = url
+        .path_segments()
+        .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+        .unwrap_or("");
+    let stem = segment.strip_suffix(".gitignore").unwrap_or(segment);
+    if stem.is_empty() {
+        url.host_str().unwrap_or("url").to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+/// Parses the API's template list (slug -> name and contents) into cache data without usage history.
+/// Templates larger than `MAX_TEMPLATE_BYTES` are left out and listed in `oversized`.
+fn parse_template_list(body: &str) -> Result<CacheData> {
+    // A BTreeMap keeps iteration (and so which entry wins on a duplicate name) independent of hashing.
+    let data: std::collections::BTreeMap<String, ToptalTemplate> = serde_json::from_str(body)?;
+
+    let mut templates = Vec::new();
+    let mut contents = std::collections::HashMap::new();
+    let mut oversized = Vec::new();
+    let mut slugs = std::collections::HashMap::new();
+
+    for (key, val) in data {
+        // Pathological entries are dropped whole rather than held in memory and the cache.
+        if val.contents.len() > MAX_TEMPLATE_BYTES {
+            oversized.push(val.name);
+            continue;
+        }
+        templates.push(val.name.clone());
+        slugs.insert(val.name.clone(), key);
+        contents.insert(val.name, val.contents);
+    }
+
+    templates.sort();
+    templates.dedup();
+    oversized.sort();
+    oversized.dedup();
+
+    Ok(CacheData {
+        templates,
+        contents,
+        last_used: std::collections::HashMap::new(),
+        slugs,
+        oversized,
+    })
+}
+
+/// Warning naming the templates a fetch skipped for being too large, if any.
+pub fn oversized_notice(data: &CacheData) -> Option<String> {
+    if data.oversized.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Skipped {} template(s) larger than {} KiB: {}",
+        data.oversized.len(),
+        MAX_TEMPLATE_BYTES / 1024,
+        data.oversized.join(", ")
+    ))
+}
+
+/// Copy of `data` keeping only the `limit` most recently used contents (ties broken by name).
+/// Templates never used count as oldest; the template list and usage times are kept whole.
+fn evict_lru(data: &CacheData, limit: usize) -> CacheData {
+    let mut names: Vec<&String> = data.contents.keys().collect();
+    names.sort_by(|a, b| {
+        let used = |name: &String| data.last_used.get(name).copied().unwrap_or(0);
+        used(b).cmp(&used(a)).then_with(|| a.cmp(b))
+    });
+    let contents = names
+        .into_iter()
+        .take(limit)
+        .map(|name| (name.clone(), data.contents[name].clone()))
+        .collect();
+    CacheData {
+        templates: data.templates.clone(),
+        contents,
+        last_used: data.last_used.clone(),
+        slugs: data.slugs.clone(),
+        oversized: Vec::new(),
+    }
+}
+
+/// Seconds since the Unix epoch.
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Hex-encoded SHA-256 of `content`.
+fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Categorizes a request failure into a message the user can act on.
+fn describe_request_error(err: &reqwest::Error) -> String {
+    let host = err
+        .url()
+        .and_then(|url| url.host_str())
+        .unwrap_or("toptal.com")
+        .to_string();
+
+    if let Some(status) = err.status() {
+        return if status.is_server_error() {
+            format!("Server returned {} — try again later", status)
+        } else if status.as_u16() == 429 {
+            "Rate limited by the server — wait a moment and retry".to_string()
+        } else if status.as_u16() == 404 {
+            "Template not found on the server (404)".to_string()
+        } else {
+            format!("Server rejected the request: {}", status)
+        };
+    }
+    if err.is_timeout() {
+        return format!("Request to {} timed out — check your connection", host);
+    }
+
+    let details = error_chain_text(err);
+    if details.contains("dns") || details.contains("lookup") || details.contains("resolve") {
+        format!("Can't resolve {} — check your DNS/network", host)
+    } else if details.contains("certificate") || details.contains("tls") || details.contains("ssl") {
+        format!("TLS handshake with {} failed — check proxies or system certificates", host)
+    } else if err.is_connect() {
+        format!("Can't connect to {} — check your network or proxy settings", host)
+    } else if err.is_decode() || err.is_body() {
+        "Received an incomplete or malformed response — try again".to_string()
+    } else {
+        format!("Request to {} failed: {}", host, err)
+    }
+}
+
+/// Lowercased text of the error and all of its sources, for keyword matching.
+fn error_chain_text(err: &dyn std::error::Error) -> String {
+    let mut text = err.to_string().to_lowercase();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        text.push(' ');
+        text.push_str(&cause.to_string().to_lowercase());
+        source = cause.source();
+    }
+    text
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Client whose cache lives in `dir` instead of the user cache directory.
+    fn client_in(dir: &std::path::Path) -> ApiClient {
+        ApiClient {
+            client: reqwest::Client::new(),
+            cache_path: dir.join("cache.json"),
+            raw_dump_path: None,
+            content_limit: None,
+        }
+    }
+
+    fn sample_cache() -> CacheData {
+        CacheData {
+            templates: vec!["Go".to_string(), "Rust".to_string()],
+            contents: HashMap::from([
+                ("Go".to_string(), "*.exe".to_string()),
+                ("Rust".to_string(), "target/".to_string()),
+            ]),
+            last_used: HashMap::new(),
+            slugs: HashMap::new(),
+            oversized: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn cache_round_trips_with_its_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = client_in(dir.path());
+        client.save_cache(&sample_cache()).unwrap();
+
+        let loaded = client.load_cache().unwrap();
+        assert_eq!(loaded.templates, ["Go", "Rust"]);
+        assert_eq!(loaded.contents["Rust"], "target/");
+    }
+
+    #[test]
+    fn corrupted_cache_is_rejected_so_it_is_fetched_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = client_in(dir.path());
+        client.save_cache(&sample_cache()).unwrap();
+        let content = fs::read_to_string(client.cache_path()).unwrap();
+        fs::write(client.cache_path(), content.replace("target/", "targex/")).unwrap();
+
+        assert!(client.load_cache().is_none());
+    }
+
+    #[test]
+    fn cache_without_hash_file_is_still_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = client_in(dir.path());
+        fs::write(client.cache_path(), serde_json::to_string(&sample_cache()).unwrap()).unwrap();
+
+        let loaded = client.load_cache().unwrap();
+        assert_eq!(loaded.templates, ["Go", "Rust"]);
+        assert_eq!(loaded.contents["Go"], "*.exe");
+    }
+
+    #[test]
+    fn truncated_cache_keeps_its_complete_entries() {
+        // Field order as `save_cache` writes it: the list comes before the contents.
+        let full = r#"{"templates":["Go","Node","Rust"],"contents":{"Go":"*.exe","Node":"node_modules/","Rust":"target/"}}"#;
+        let cut = full.find("target/").unwrap();
+
+        let recovered = parse_cache_text(&full[..cut]).unwrap();
+        assert_eq!(recovered.templates, ["Go", "Node", "Rust"]);
+        assert_eq!(recovered.contents.len(), 2);
+        assert_eq!(recovered.contents["Node"], "node_modules/");
+    }
+
+    #[test]
+    fn malformed_fields_keep_the_readable_parts() {
+        let text = r#"{"templates": "oops", "contents": {"Go": "*.exe", "Bad": 7}, "last_used": []}"#;
+        let recovered = parse_cache_text(text).unwrap();
+        // An unusable list falls back to the content keys.
+        assert_eq!(recovered.templates, ["Go"]);
+        assert!(!recovered.contents.contains_key("Bad"));
+        assert!(recovered.last_used.is_empty());
+    }
+
+    #[test]
+    fn eviction_drops_least_recently_used_first() {
+        let mut cache = sample_cache();
+        cache.contents.insert("Node".to_string(), "node_modules/".to_string());
+        cache.last_used = HashMap::from([("Go".to_string(), 30), ("Rust".to_string(), 10)]);
+
+        // Never-used Node goes first, then Rust (older than Go).
+        let kept = evict_lru(&cache, 2);
+        let mut names: Vec<&String> = kept.contents.keys().collect();
+        names.sort();
+        assert_eq!(names, ["Go", "Rust"]);
+        let kept = evict_lru(&cache, 1);
+        assert!(kept.contents.contains_key("Go"));
+        assert_eq!(kept.contents.len(), 1);
+        assert_eq!(kept.templates, cache.templates);
+    }
+
+    #[test]
+    fn oversized_templates_are_skipped_and_reported() {
+        let huge = "x".repeat(MAX_TEMPLATE_BYTES + 1);
+        let body = serde_json::json!({
+            "rust": {"name": "Rust", "contents": "target/"},
+            "huge": {"name": "Huge", "contents": huge},
+        })
+        .to_string();
+
+        let data = parse_template_list(&body).unwrap();
+        assert_eq!(data.templates, ["Rust"]);
+        assert!(!data.contents.contains_key("Huge"));
+        assert_eq!(data.slugs["Rust"], "rust");
+        assert_eq!(data.oversized, ["Huge"]);
+        let notice = oversized_notice(&data).unwrap();
+        assert!(notice.starts_with("Skipped 1 template(s) larger than 1024 KiB: Huge"), "{notice}");
+        assert_eq!(oversized_notice(&sample_cache()), None);
+    }
+}
+
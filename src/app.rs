@@ -1,16 +1,95 @@
+use anyhow::Result;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
-use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use regex::{Regex, RegexBuilder};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use unicode_width::UnicodeWidthStr;
 
-#[derive(Debug, PartialEq)]
+use crate::aliases;
+use crate::audit;
+use crate::categories;
+use crate::cli::Args;
+use crate::config::{Config, ListOrder, SearchMode, SectionOrder};
+use crate::error::AppError;
+use crate::models::CacheData;
+use crate::stats;
+use crate::symbols::{self, Symbols};
+use crate::gitignore::{self, LineEnding, WriteMode, WriteOutcome};
+use crate::keymap::Keymap;
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
 pub enum InputMode {
     Normal,
+    #[default]
     Editing,
     Confirm,
+    /// Typing the name and lines of a new custom section.
+    CustomSection,
+    /// Jumping through the list by first letter (entered with `'`).
+    Jump,
+    /// Reading the key list (opened with `?`); any other key closes it.
+    Help,
 }
 
-#[derive(Debug, PartialEq)]
+/// A custom section being entered: first its name, then one line per Enter.
+#[derive(Debug, Default)]
+pub struct CustomDraft {
+    pub name: Option<String>,
+    pub lines: Vec<String>,
+    pub input: String,
+}
+
+/// Where a template's content stands with respect to on-demand fetching.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FetchStatus {
+    /// Not loaded and not requested yet.
+    NotFetched,
+    /// A fetch is in flight.
+    Fetching,
+    /// Content is available.
+    Loaded,
+    /// The fetch (and its retry) failed; `R` tries again.
+    Failed,
+}
+
+/// Restricts the list to templates from one origin.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SourceFilter {
+    #[default]
+    All,
+    /// Templates from gitignore.io.
+    Remote,
+    /// Templates from `--template-dir`.
+    Local,
+    /// Custom sections from the config.
+    Custom,
+}
+
+impl SourceFilter {
+    /// The filter after this one in the cycle.
+    pub fn next(self) -> Self {
+        match self {
+            SourceFilter::All => SourceFilter::Remote,
+            SourceFilter::Remote => SourceFilter::Local,
+            SourceFilter::Local => SourceFilter::Custom,
+            SourceFilter::Custom => SourceFilter::All,
+        }
+    }
+
+    /// Short name shown in the list title.
+    pub fn label(self) -> &'static str {
+        match self {
+            SourceFilter::All => "all",
+            SourceFilter::Remote => "remote",
+            SourceFilter::Local => "local",
+            SourceFilter::Custom => "custom",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PreviewMode {
     Highlighted,
     Combined,
@@ -22,12 +101,104 @@ pub enum ConfirmAction {
     Overwrite,
 }
 
+/// View state the preview text depends on, besides the selection and contents
+/// (changes to those invalidate the memoized preview explicitly).
+#[derive(PartialEq)]
+struct PreviewKey {
+    mode: PreviewMode,
+    compared: Option<(String, String)>,
+    shown: Option<String>,
+    peeked: Option<String>,
+    strip_comments: bool,
+    trim_whitespace: bool,
+}
+
+/// Ranking used for search results: a name equal to `query` first, then higher score, then
+/// deterministic tie-breaks (names starting with `query`, then shorter names, then A-Z).
+/// Each side is `(score, name, lowercase name)` and `query` must be lowercase, so nothing is
+/// lowercased per comparison. This is a total order over distinct names, so results never
+/// depend on input order.
+fn compare_matches(query: &str, a: (i64, &str, &str), b: (i64, &str, &str)) -> std::cmp::Ordering {
+    let a_exact = a.2 == query;
+    let b_exact = b.2 == query;
+    let a_prefix = a.2.starts_with(query);
+    let b_prefix = b.2.starts_with(query);
+    b_exact
+        .cmp(&a_exact)
+        .then_with(|| b.0.cmp(&a.0))
+        .then_with(|| b_prefix.cmp(&a_prefix))
+        .then_with(|| a.1.len().cmp(&b.1.len()))
+        .then_with(|| a.1.cmp(b.1))
+}
+
+/// First line of a possibly multi-line message.
+fn first_line(text: &str) -> &str {
+    text.lines().find(|line| !line.trim().is_empty()).unwrap_or(text).trim()
+}
+
+/// Body written for a selected template that has no patterns.
+const EMPTY_TEMPLATE_NOTE: &str = "# (empty template)";
+
+/// Soft cap on Combined preview lines; beyond this a truncation notice is shown instead.
+const MAX_PREVIEW_LINES: usize = 2000;
+
+/// Cuts a preview down to `MAX_PREVIEW_LINES`, noting how much was left out.
+/// Only the preview is truncated; the generated file is always complete.
+fn truncate_preview(preview: String) -> String {
+    let total = preview.lines().count();
+    if total <= MAX_PREVIEW_LINES {
+        return preview;
+    }
+    let mut truncated: String = preview
+        .lines()
+        .take(MAX_PREVIEW_LINES)
+        .flat_map(|line| [line, "\n"])
+        .collect();
+    truncated.push_str(&format!(
+        "\n... preview truncated, {} more lines; file will still be written in full\n",
+        total - MAX_PREVIEW_LINES
+    ));
+    truncated
+}
+
+/// Outcome of writing every target with `App::save`.
+#[derive(Default)]
+pub struct SaveReport {
+    /// Files written, with what each write did.
+    pub written: Vec<(PathBuf, WriteOutcome)>,
+    /// One `path: error` message per target that couldn't be written.
+    pub failures: Vec<String>,
+}
+
+impl SaveReport {
+    /// Paths of the files that were written.
+    pub fn written_paths(&self) -> Vec<PathBuf> {
+        self.written.iter().map(|(path, _)| path.clone()).collect()
+    }
+
+    /// Error naming each failed target and, when some targets did succeed, which ones.
+    pub fn error(&self) -> Option<String> {
+        if self.failures.is_empty() {
+            return None;
+        }
+        let mut message = format!("Failed to write {} target(s): {}", self.failures.len(), self.failures.join("; "));
+        if !self.written.is_empty() {
+            let written: Vec<String> = self.written.iter().map(|(path, _)| path.display().to_string()).collect();
+            message.push_str(&format!(". Wrote {}", written.join(", ")));
+        }
+        Some(message)
+    }
+}
+
 /// Application state and business logic.
 pub struct App {
     /// List of all available template names.
     pub templates: Vec<String>,
-    /// List of template names that match the current search query.
-    pub filtered_templates: Vec<String>,
+    /// Indices into `templates` of the names that match the current search query, in display
+    /// order (indices avoid cloning every name on each keystroke).
+    pub filtered_templates: Vec<usize>,
+    /// Lowercased `templates`, index for index, so filtering never lowercases a name per keystroke.
+    templates_lower: Vec<String>,
     /// Set of selected template names.
     pub selected_templates: HashSet<String>,
     /// Current index in the filtered templates list.
@@ -40,8 +211,12 @@ pub struct App {
     pub template_contents: HashMap<String, String>,
     /// Whether the application is still fetching data.
     pub is_loading: bool,
+    /// Whether cached data is shown while a background refresh is in flight.
+    pub refreshing: bool,
+    /// Whether the background refresh failed, leaving possibly outdated cached data.
+    pub refresh_failed: bool,
     /// Current error message to display in the UI.
-    pub error: Option<String>,
+    pub error: Option<AppError>,
     /// Current success/info notification to display in the UI.
     pub notification: Option<String>,
     /// Scroll offset for the preview pane.
@@ -52,60 +227,400 @@ pub struct App {
     pub preview_mode: PreviewMode,
     /// Currently selected action in the confirmation modal.
     pub confirm_action: Option<ConfirmAction>,
+    /// Result of appending to the existing file, shown in the confirmation modal when toggled on.
+    pub append_preview: Option<String>,
+    /// Vertical scroll offset of the append preview.
+    pub append_preview_scroll: u16,
+    /// Write mode requested on the command line, preselected (or applied with `--yes`) for existing files.
+    pub write_mode: Option<WriteMode>,
+    /// Accept confirmation prompts without showing them (`--yes`).
+    pub assume_yes: bool,
+    /// Lines an Overwrite would remove, once it is waiting for a second confirmation.
+    pub overwrite_removals: Option<usize>,
     /// Whether the app should exit after the next successful save.
     pub should_quit_after_save: bool,
-    /// Directory where the .gitignore should be written.
-    pub output_dir: PathBuf,
+    /// Directories where the .gitignore should be written (at least one).
+    pub output_dirs: Vec<PathBuf>,
+    /// Whether the output directory is the remembered one rather than the cwd or `--dir`.
+    pub remembered_output_dir: bool,
     /// Cached preview pane height (content rows, excluding borders).
     pub preview_height: u16,
+    /// Cached preview pane width (content columns, excluding borders).
+    pub preview_width: u16,
+    /// Whether long preview lines are wrapped (otherwise scrolled horizontally).
+    pub preview_wrap: bool,
+    /// Horizontal scroll offset for the preview pane when wrapping is off.
+    pub preview_hscroll: u16,
+    /// Whether the preview colors comments, negations and directory patterns.
+    pub preview_syntax: bool,
+    /// Line ending style used when writing the .gitignore.
+    pub eol: LineEnding,
+    /// Whether writes may resolve outside the output directories.
+    pub allow_outside: bool,
+    /// Whether a standard .gitattributes is written alongside each .gitignore.
+    pub write_gitattributes: bool,
+    /// Browse-only mode: every write path is disabled.
+    pub read_only: bool,
+    /// Template names to preselect once templates load, with where they came from
+    /// (the project defaults file or an imported selection).
+    pub pending_defaults: Option<(&'static str, Vec<String>)>,
+    /// Fetch status of templates whose content was requested on demand (see `fetch_status`).
+    content_status: HashMap<String, FetchStatus>,
+    /// Template kept in the Highlighted preview regardless of cursor movement.
+    pub pinned_preview: Option<String>,
+    /// Templates marked for comparison with `C`: the first, then the second once chosen.
+    /// While both are set the preview shows a diff between them.
+    pub comparison: Option<(String, Option<String>)>,
+    /// Selected templates in the order they were picked, for the `selection` section order.
+    /// May hold stale names; only entries still in `selected_templates` count.
+    pub selection_order: Vec<String>,
+    /// Whether the Combined preview also shows the highlighted (unselected) template.
+    pub peek: bool,
+    /// One line per file written this session, printed after the TUI exits.
+    pub write_summary: Vec<String>,
+    /// Whether the preview fills the whole screen (toggled with `z`).
+    pub preview_zoomed: bool,
+    /// Last built preview and the view state it was built for; `None` when it must be rebuilt.
+    preview_cache: RefCell<Option<(PreviewKey, String)>>,
+    /// Generate one flat pattern list instead of per-template sections (`--flat`).
+    pub flat: bool,
+    /// Glyph set used for drawing (ASCII with `--ascii` or on a non-Unicode terminal).
+    pub symbols: &'static Symbols,
+    /// Animation frame counter, advanced on every tick while loading.
+    pub spinner_frame: usize,
+    /// Whether generated output drops the upstream comment-only lines.
+    pub strip_comments: bool,
+    /// Trim trailing whitespace from template lines (on unless `--keep-whitespace`).
+    pub trim_whitespace: bool,
+    /// Persistent user preferences.
+    pub config: Config,
+    /// Whether blocked templates are temporarily shown in the list.
+    pub show_blocked: bool,
+    /// Write counts per template, used by the `most_used` list order.
+    pub usage_counts: HashMap<String, u64>,
+    /// Which template origins are listed (cycled with `S`).
+    pub source_filter: SourceFilter,
+    /// How the search query is matched (cycled with Ctrl+F).
+    pub search_mode: SearchMode,
+    /// Compiled query in Regex mode; kept from the last valid pattern.
+    search_regex: Option<Regex>,
+    /// First matching line of each template that a Content search found only in its contents.
+    pub content_matches: HashMap<String, String>,
+    /// Index of the saved filter last applied; shown while the query still matches it.
+    active_filter: Option<usize>,
+    /// Why the current query can't be used (e.g. an invalid regex), shown in the search pane.
+    pub search_error: Option<String>,
+    /// Templates read from `--template-dir`, merged over the remote data on every load.
+    pub local_template_contents: HashMap<String, String>,
+    /// API slug of each remote template by name (matched by search and `--gen` too).
+    pub template_slugs: HashMap<String, String>,
+    /// Names of templates that come from the local template directory.
+    pub local_templates: HashSet<String>,
+    /// Templates added upstream since the cached list, badged NEW until previewed or the app exits.
+    pub new_templates: HashSet<String>,
+    /// Names of templates that are custom sections from the config or an imported selection.
+    pub custom_templates: HashSet<String>,
+    /// Custom sections from `--import-selection`; used for this run only, never saved to the config.
+    pub imported_sections: BTreeMap<String, String>,
+    /// Custom section being entered, while in `InputMode::CustomSection`.
+    pub custom_draft: Option<CustomDraft>,
+    /// Normal-mode keys, with the config's `keybindings` applied.
+    pub keymap: Keymap,
+    /// Scroll offset of the help overlay.
+    pub help_scroll: u16,
 }
 
 impl App {
-    pub fn new(output_dir: PathBuf) -> Self {
+    pub fn new(args: &Args, config: Config) -> Self {
         Self {
             templates: Vec::new(),
             filtered_templates: Vec::new(),
+            templates_lower: Vec::new(),
             selected_templates: HashSet::new(),
             highlighted_index: 0,
             search_query: String::new(),
-            input_mode: InputMode::Editing,
+            input_mode: args.start_mode,
             template_contents: HashMap::new(),
             is_loading: true,
+            refreshing: false,
+            refresh_failed: false,
             error: None,
             notification: None,
             preview_scroll: 0,
             matcher: SkimMatcherV2::default(),
             preview_mode: PreviewMode::Highlighted,
             confirm_action: None,
+            append_preview: None,
+            append_preview_scroll: 0,
+            write_mode: args.write_mode,
+            assume_yes: args.yes,
+            overwrite_removals: None,
             should_quit_after_save: false,
-            output_dir,
+            output_dirs: args.output_dirs.clone(),
+            remembered_output_dir: false,
             preview_height: 0,
+            preview_width: 0,
+            preview_wrap: true,
+            preview_hscroll: 0,
+            preview_syntax: true,
+            eol: args.eol,
+            allow_outside: args.allow_outside,
+            write_gitattributes: args.gitattributes,
+            read_only: args.read_only,
+            pending_defaults: None,
+            imported_sections: args
+                .imported
+                .as_ref()
+                .map(|recipe| recipe.custom_sections.clone())
+                .unwrap_or_default(),
+            content_status: HashMap::new(),
+            pinned_preview: None,
+            comparison: None,
+            selection_order: Vec::new(),
+            peek: false,
+            preview_zoomed: false,
+            write_summary: Vec::new(),
+            preview_cache: RefCell::new(None),
+            flat: args.flat,
+            symbols: symbols::select(args.ascii),
+            spinner_frame: 0,
+            strip_comments: args.no_comments,
+            trim_whitespace: !args.keep_whitespace,
+            search_mode: config.search_mode,
+            // Invalid overrides are rejected when the config loads; fall back to the defaults here.
+            keymap: Keymap::resolve(&config.keybindings).unwrap_or_default(),
+            config,
+            show_blocked: false,
+            usage_counts: HashMap::new(),
+            source_filter: SourceFilter::All,
+            search_regex: None,
+            content_matches: HashMap::new(),
+            active_filter: None,
+            search_error: None,
+            local_template_contents: HashMap::new(),
+            template_slugs: HashMap::new(),
+            local_templates: HashSet::new(),
+            custom_templates: HashSet::new(),
+            new_templates: HashSet::new(),
+            custom_draft: None,
+            help_scroll: 0,
         }
     }
 
     pub fn set_templates(&mut self, templates: Vec<String>) {
         self.templates = templates;
         self.templates.sort();
+        self.templates.dedup();
+        self.templates_lower = self.templates.iter().map(|t| t.to_lowercase()).collect();
+        // The old indices point into the previous list; an invalid regex would otherwise keep them.
+        self.filtered_templates.clear();
         self.apply_filter();
         self.is_loading = false;
     }
 
+    /// Replaces the template data with `cache`, then layers local templates and custom sections on top.
+    /// A local template overrides a remote one with the same (case-insensitive) name.
+    /// The selection (minus templates that no longer exist) and the highlighted template survive the swap.
+    pub fn load_data(&mut self, cache: CacheData) {
+        let highlighted = self.get_current_highlighted();
+        self.invalidate_preview();
+        let mut templates = cache.templates;
+        self.template_contents = cache.contents;
+        self.template_slugs = cache.slugs;
+        self.local_templates.clear();
+
+        // Sorted so that case-insensitive clashes between local files resolve the same way every run.
+        let mut local: Vec<(&String, &String)> = self.local_template_contents.iter().collect();
+        local.sort();
+        for (name, content) in local {
+            let name = match templates.iter().find(|t| t.eq_ignore_ascii_case(name)) {
+                Some(remote) => remote.clone(),
+                None => {
+                    templates.push(name.clone());
+                    name.clone()
+                }
+            };
+            self.template_contents.insert(name.clone(), content.clone());
+            self.local_templates.insert(name);
+        }
+
+        let contents = &self.template_contents;
+        self.content_status.retain(|name, _| !contents.contains_key(name));
+
+        self.custom_templates.clear();
+        let imported = self
+            .imported_sections
+            .iter()
+            .filter(|(name, _)| !self.config.custom_sections.contains_key(*name));
+        for (name, content) in self.config.custom_sections.iter().chain(imported) {
+            if !templates.contains(name) {
+                templates.push(name.clone());
+            }
+            self.template_contents.insert(name.clone(), content.clone());
+            self.custom_templates.insert(name.clone());
+        }
+
+        self.set_templates(templates);
+        let templates = &self.templates;
+        self.selected_templates.retain(|t| templates.contains(t));
+        if let Some(index) = highlighted.and_then(|name| self.filtered_names().position(|t| *t == name)) {
+            self.highlighted_index = index;
+        }
+    }
+
+    /// Summarizes how `new` differs from the currently loaded template list.
+    /// Returns `None` on the initial load, when there is nothing to compare against.
+    pub fn describe_template_changes(&self, new: &[String]) -> Option<String> {
+        let (added, removed) = self.template_change_counts(new)?;
+        if added == 0 && removed == 0 {
+            Some("Templates are up to date.".to_string())
+        } else {
+            Some(format!(
+                "{} new template{} added, {} removed.",
+                added,
+                if added == 1 { "" } else { "s" },
+                removed
+            ))
+        }
+    }
+
+    /// Numbers of templates added and removed in `new` compared with the loaded list,
+    /// or `None` when nothing is loaded yet.
+    pub fn template_change_counts(&self, new: &[String]) -> Option<(usize, usize)> {
+        let (added, old) = self.template_changes(new)?;
+        let new: HashSet<&String> = new.iter().collect();
+        Some((added.len(), old.difference(&new).count()))
+    }
+
+    /// Clears the NEW badge of the template whose own preview is now shown (highlighted or pinned).
+    /// Called after each handled event rather than from drawing, so only what the user brought
+    /// into view counts as seen.
+    pub fn mark_shown_seen(&mut self) {
+        if self.preview_mode == PreviewMode::Highlighted
+            && self.compared_pair().is_none()
+            && let Some(shown) = self.pinned_preview.clone().or_else(|| self.get_current_highlighted())
+        {
+            self.new_templates.remove(&shown);
+        }
+    }
+
+    /// Badges the templates in `new` that the loaded list doesn't have (nothing on the first load).
+    pub fn mark_new_templates(&mut self, new: &[String]) {
+        if let Some((added, _)) = self.template_changes(new) {
+            let added: Vec<String> = added.into_iter().cloned().collect();
+            self.new_templates.extend(added);
+        }
+    }
+
+    /// Names in `new` missing from the loaded remote list, and that list; `None` before the first load.
+    fn template_changes<'a>(&'a self, new: &'a [String]) -> Option<(Vec<&'a String>, HashSet<&'a String>)> {
+        if self.templates.is_empty() {
+            return None;
+        }
+        let old: HashSet<&String> = self
+            .templates
+            .iter()
+            .filter(|t| !(self.local_templates.contains(*t) || self.custom_templates.contains(*t)) || new.contains(t))
+            .collect();
+        let added = new.iter().filter(|t| !old.contains(t)).collect();
+        Some((added, old))
+    }
+
+    /// Whether `template` passes the blocked and source filters (before the search query).
+    fn is_listed(&self, template: &str) -> bool {
+        let source = if self.local_templates.contains(template) {
+            SourceFilter::Local
+        } else if self.custom_templates.contains(template) {
+            SourceFilter::Custom
+        } else {
+            SourceFilter::Remote
+        };
+        (self.show_blocked || !self.is_blocked(template))
+            && (self.source_filter == SourceFilter::All || self.source_filter == source)
+    }
+
+    /// Shows only the next template origin in the list.
+    pub fn cycle_source_filter(&mut self) {
+        self.source_filter = self.source_filter.next();
+        self.apply_filter();
+    }
+
+    pub fn is_blocked(&self, template: &str) -> bool {
+        self.config.blocked_templates.iter().any(|b| b == template)
+    }
+
     pub fn apply_filter(&mut self) {
+        self.search_error = None;
+        self.content_matches.clear();
         if self.search_query.is_empty() {
-            self.filtered_templates = self.templates.clone();
+            let mut listed: Vec<usize> = (0..self.templates.len())
+                .filter(|&i| self.is_listed(&self.templates[i]))
+                .collect();
+            if self.config.list_order == ListOrder::MostUsed {
+                let (counts, templates) = (&self.usage_counts, &self.templates);
+                // Stable, so equally used templates stay alphabetical.
+                listed.sort_by_key(|&i| std::cmp::Reverse(counts.get(&templates[i]).copied().unwrap_or(0)));
+            }
+            self.filtered_templates = listed;
         } else {
-            let mut matches: Vec<(i64, String)> = self
+            if self.search_mode == SearchMode::Regex {
+                match RegexBuilder::new(&self.search_query).case_insensitive(true).build() {
+                    Ok(regex) => self.search_regex = Some(regex),
+                    Err(e) => {
+                        // Keep the previous results rather than clearing the list mid-typing.
+                        self.search_error = Some(format!("Invalid regex: {}", first_line(&e.to_string())));
+                        return;
+                    }
+                }
+            }
+
+            let query = self.search_query.to_lowercase();
+            let mut content_matches = HashMap::new();
+            let mut matches: Vec<(i64, &str, &str, usize)> = self
                 .templates
                 .iter()
-                .filter_map(|t| {
-                    self.matcher
-                        .fuzzy_match(t, &self.search_query)
-                        .map(|score| (score, t.clone()))
+                .zip(&self.templates_lower)
+                .enumerate()
+                .filter(|(_, (t, _))| self.is_listed(t))
+                .filter_map(|(i, (t, lower))| {
+                    // Score against the name, its slug and every alias, keeping the best match.
+                    if self.search_mode == SearchMode::Prefix {
+                        // Only the name itself, so results read like autocomplete.
+                        return lower.starts_with(&query).then_some((0, t.as_str(), lower.as_str(), i));
+                    }
+                    // Slugs and aliases are lowercase already.
+                    let name_score = std::iter::once((t.as_str(), lower.as_str()))
+                        .chain(self.template_slugs.get(t).map(|slug| (slug.as_str(), slug.as_str())))
+                        .chain(aliases::aliases_for(t).iter().map(|alias| (*alias, *alias)))
+                        .filter_map(|(candidate, lower)| self.match_score(candidate, lower, &query))
+                        .max();
+                    if name_score.is_some() || self.search_mode != SearchMode::Content {
+                        return name_score.map(|score| (score, t.as_str(), lower.as_str(), i));
+                    }
+                    // Content matches rank below name matches and remember the line that matched.
+                    let line = self
+                        .template_contents
+                        .get(t)?
+                        .lines()
+                        .map(str::trim)
+                        .find(|line| line.to_lowercase().contains(&query))?;
+                    content_matches.insert(t.clone(), line.to_string());
+                    Some((-1, t.as_str(), lower.as_str(), i))
                 })
                 .collect();
 
-            matches.sort_by(|a, b| b.0.cmp(&a.0));
-            self.filtered_templates = matches.into_iter().map(|(_, t)| t).collect();
+            if self.search_mode == SearchMode::Prefix {
+                matches.sort_by_key(|&(_, t, lower, _)| (lower, t));
+            } else {
+                matches.sort_by(|a, b| compare_matches(&query, (a.0, a.1, a.2), (b.0, b.1, b.2)));
+            }
+            // An exact name is almost certainly what the user wants: highlight it.
+            let exact = matches.first().is_some_and(|&(_, _, lower, _)| lower == query);
+            self.filtered_templates = matches.into_iter().map(|(_, _, _, i)| i).collect();
+            self.content_matches = content_matches;
+            if exact {
+                self.highlighted_index = 0;
+            }
         }
 
         if self.highlighted_index >= self.filtered_templates.len()
@@ -117,10 +632,107 @@ impl App {
         }
     }
 
+    /// Scores `candidate` (with its lowercase form `lower`) against the lowercase `query` in the
+    /// active search mode; `None` means no match.
+    /// Substring and Regex matches all score equally, leaving order to the tie-breaks.
+    fn match_score(&self, candidate: &str, lower: &str, query: &str) -> Option<i64> {
+        match self.search_mode {
+            SearchMode::Fuzzy => self.matcher.fuzzy_match(candidate, &self.search_query),
+            SearchMode::Prefix => lower.starts_with(query).then_some(0),
+            SearchMode::Substring | SearchMode::Content => lower.contains(query).then_some(0),
+            SearchMode::Regex => self.search_regex.as_ref()?.is_match(candidate).then_some(0),
+        }
+    }
+
+    /// Character positions in `name` matched by the current query, for highlighting.
+    pub fn match_indices(&self, name: &str) -> Vec<usize> {
+        if self.search_query.is_empty() {
+            return Vec::new();
+        }
+        let byte_range = match self.search_mode {
+            SearchMode::Fuzzy => {
+                return self
+                    .matcher
+                    .fuzzy_indices(name, &self.search_query)
+                    .map(|(_, indices)| indices)
+                    .unwrap_or_default();
+            }
+            SearchMode::Prefix => {
+                let query_len = self.search_query.chars().count();
+                return if name.to_lowercase().starts_with(&self.search_query.to_lowercase()) {
+                    (0..query_len).collect()
+                } else {
+                    Vec::new()
+                };
+            }
+            SearchMode::Substring | SearchMode::Content => {
+                let query: Vec<char> = self.search_query.to_lowercase().chars().collect();
+                let chars: Vec<char> = name.to_lowercase().chars().collect();
+                return chars
+                    .windows(query.len())
+                    .position(|window| window == query.as_slice())
+                    .map(|start| (start..start + query.len()).collect())
+                    .unwrap_or_default();
+            }
+            SearchMode::Regex => match self.search_regex.as_ref().and_then(|r| r.find(name)) {
+                Some(m) => m.range(),
+                None => return Vec::new(),
+            },
+        };
+        name.char_indices()
+            .enumerate()
+            .filter(|(_, (byte, _))| byte_range.contains(byte))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Applies saved filter `index` (0-based): sets its query and, if given, its search mode.
+    pub fn apply_saved_filter(&mut self, index: usize) {
+        let Some(filter) = self.config.saved_filters.get(index).cloned() else {
+            self.notification = None;
+            self.error = Some(AppError::Input(format!("No saved filter {} in the config.", index + 1)));
+            return;
+        };
+        if let Some(mode) = filter.mode {
+            self.search_mode = mode;
+            self.search_regex = None;
+        }
+        self.search_query = filter.query;
+        self.active_filter = Some(index);
+        self.highlighted_index = 0;
+        self.error = None;
+        self.notification = None;
+        self.apply_filter();
+    }
+
+    /// Name of the saved filter whose query is currently in the search box, if any.
+    pub fn active_filter_name(&self) -> Option<&str> {
+        let filter = self.config.saved_filters.get(self.active_filter?)?;
+        (filter.query == self.search_query).then_some(filter.name.as_str())
+    }
+
+    /// Switches between Prefix and Fuzzy search (from any other mode, to Prefix) and re-filters.
+    pub fn toggle_prefix_search(&mut self) {
+        self.search_mode = if self.search_mode == SearchMode::Prefix {
+            SearchMode::Fuzzy
+        } else {
+            SearchMode::Prefix
+        };
+        self.search_regex = None;
+        self.apply_filter();
+    }
+
+    /// Switches to the next search mode and re-filters.
+    pub fn cycle_search_mode(&mut self) {
+        self.search_mode = self.search_mode.next();
+        self.search_regex = None;
+        self.apply_filter();
+    }
+
     pub fn next(&mut self) {
         if !self.filtered_templates.is_empty() {
             self.highlighted_index = (self.highlighted_index + 1) % self.filtered_templates.len();
-            self.preview_scroll = 0;
+            self.reset_preview_scroll_unless_pinned();
         }
     }
 
@@ -131,68 +743,628 @@ impl App {
             } else {
                 self.highlighted_index = self.filtered_templates.len() - 1;
             }
+            self.reset_preview_scroll_unless_pinned();
+        }
+    }
+
+    fn reset_preview_scroll_unless_pinned(&mut self) {
+        if self.pinned_preview.is_none() {
             self.preview_scroll = 0;
+            self.preview_hscroll = 0;
         }
     }
 
+    /// Recomputes the preview from the current contents and brings its scroll offsets back in range.
+    pub fn refresh_preview(&mut self) {
+        self.invalidate_preview();
+        self.clamp_preview_scroll();
+        self.error = None;
+        self.notification = Some("Preview refreshed.".to_string());
+    }
+
+    /// Pins the highlighted template's preview, or unpins it if already pinned.
+    pub fn toggle_pinned_preview(&mut self) {
+        self.pinned_preview = match self.pinned_preview {
+            Some(_) => None,
+            None => self.get_current_highlighted(),
+        };
+        self.preview_scroll = 0;
+        self.preview_hscroll = 0;
+    }
+
+    /// Adds the highlighted template to the persistent blocklist, or removes it if already blocked.
+    pub fn toggle_blocked_highlighted(&mut self) -> Result<()> {
+        let Some(template) = self.get_current_highlighted() else {
+            return Ok(());
+        };
+
+        let blocked = if self.is_blocked(&template) {
+            self.config.blocked_templates.retain(|b| b != &template);
+            false
+        } else {
+            self.config.blocked_templates.push(template.clone());
+            self.config.blocked_templates.sort();
+            true
+        };
+        self.config.save()?;
+        self.apply_filter();
+        self.notification = Some(if blocked {
+            format!("Blocked {} (press B to show blocked templates).", template)
+        } else {
+            format!("Unblocked {}.", template)
+        });
+        Ok(())
+    }
+
+    /// Switches between the regular and compact list layouts and persists the choice.
+    pub fn toggle_compact_list(&mut self) -> Result<()> {
+        self.config.compact_list = !self.config.compact_list;
+        self.config.save()
+    }
+
+    /// Temporarily shows or hides blocked templates in the list.
+    pub fn toggle_show_blocked(&mut self) {
+        self.show_blocked = !self.show_blocked;
+        self.apply_filter();
+    }
+
     /// Toggles selection of the currently highlighted template and clears any errors.
     pub fn toggle_selection(&mut self) {
-        if let Some(template) = self.filtered_templates.get(self.highlighted_index) {
-            if self.selected_templates.contains(template) {
-                self.selected_templates.remove(template);
+        self.invalidate_preview();
+        let mut added = None;
+        if let Some(template) = self.get_current_highlighted() {
+            if self.selected_templates.contains(&template) {
+                self.selected_templates.remove(&template);
             } else {
-                self.selected_templates.insert(template.clone());
+                added = Some(template.clone());
+                self.select(template);
             }
         }
         self.error = None;
         self.notification = None;
+        if let Some(template) = added
+            && self.template_contents.get(&template).is_some_and(|c| c.trim().is_empty())
+        {
+            self.notification = Some(format!("{} is an empty template and adds nothing.", template));
+        }
+
+        if self.config.auto_combined_preview && self.selected_templates.len() > 1 {
+            self.show_combined_preview();
+        }
+
+        // Warn once, when the selection grows past the soft cap.
+        if let Some(limit) = self.config.selection_warning.filter(|limit| *limit > 0)
+            && self.selected_templates.len() == limit + 1
+        {
+            self.notification = Some(format!(
+                "{} templates selected; a .gitignore this large is usually redundant. Consider trimming the selection.",
+                self.selected_templates.len()
+            ));
+        }
+    }
+
+    /// Selects the top filtered template (keeping any existing selection) and highlights it.
+    pub fn select_top_match(&mut self) -> Option<String> {
+        let top = self.filtered_name(0)?.clone();
+        self.select(top.clone());
+        self.highlighted_index = 0;
+        self.reset_preview_scroll_unless_pinned();
+        Some(top)
+    }
+
+    /// Adds `template` to the selection, after everything already selected in selection order.
+    pub fn select(&mut self, template: String) {
+        self.invalidate_preview();
+        self.selection_order.retain(|t| *t != template);
+        self.selection_order.push(template.clone());
+        self.selected_templates.insert(template);
+    }
+
+    /// Moves the highlighted selected template `delta` places in the selection order.
+    /// Only meaningful with the `selection` section order.
+    pub fn move_highlighted_selection(&mut self, delta: isize) {
+        if self.config.section_order != SectionOrder::Selection {
+            self.notification = None;
+            self.error = Some(AppError::Input(
+                "Reordering needs \"section_order\": \"selection\" in the config.".to_string(),
+            ));
+            return;
+        }
+        let Some(template) = self.get_current_highlighted().filter(|t| self.selected_templates.contains(t)) else {
+            return;
+        };
+        let mut order: Vec<String> = self.ordered(self.selected_templates.iter().collect())
+            .into_iter()
+            .cloned()
+            .collect();
+        let Some(index) = order.iter().position(|t| *t == template) else {
+            return;
+        };
+        let target = index.saturating_add_signed(delta).min(order.len() - 1);
+        order.swap(index, target);
+        self.selection_order = order;
+        self.invalidate_preview();
+    }
+
+    /// Sorts section names by the configured section order.
+    fn ordered<'a>(&self, mut names: Vec<&'a String>) -> Vec<&'a String> {
+        match self.config.section_order {
+            SectionOrder::Alphabetical => names.sort(),
+            SectionOrder::Category => names.sort_by_key(|t| (categories::category_for(t), *t)),
+            SectionOrder::Selection => names.sort_by_key(|t| {
+                let position = self.selection_order.iter().position(|s| s == *t);
+                (position.unwrap_or(usize::MAX), *t)
+            }),
+        }
+        names
+    }
+
+    /// Deselects every selected template that matches the current filter, returning how many.
+    pub fn deselect_filtered(&mut self) -> usize {
+        let before = self.selected_templates.len();
+        self.invalidate_preview();
+        let filtered: Vec<String> = self.filtered_names().cloned().collect();
+        for template in &filtered {
+            self.selected_templates.remove(template);
+        }
+        before - self.selected_templates.len()
+    }
+
+    /// Opens the input for a new custom section.
+    pub fn start_custom_section(&mut self) {
+        self.error = None;
+        self.notification = None;
+        self.custom_draft = Some(CustomDraft::default());
+        self.input_mode = InputMode::CustomSection;
+    }
+
+    /// Enters jump mode, where each letter typed moves to a template starting with it.
+    pub fn start_jump(&mut self) {
+        self.error = None;
+        self.notification = Some("Jump: type a letter (again to cycle), Esc to stop.".to_string());
+        self.input_mode = InputMode::Jump;
+    }
+
+    /// Leaves jump mode.
+    pub fn stop_jump(&mut self) {
+        self.notification = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Highlights the first listed template starting with `c` (ignoring case), or, when the
+    /// highlighted one already does, the next such template (wrapping around).
+    pub fn jump_to_letter(&mut self, c: char) {
+        let starts_with = |t: &String| t.chars().next().is_some_and(|first| first.to_lowercase().eq(c.to_lowercase()));
+        let len = self.filtered_templates.len();
+        let from = match self.filtered_name(self.highlighted_index) {
+            Some(current) if starts_with(current) => self.highlighted_index + 1,
+            _ => 0,
+        };
+        let found = (0..len)
+            .map(|offset| (from + offset) % len)
+            .find(|&i| starts_with(&self.templates[self.filtered_templates[i]]));
+        match found {
+            Some(index) => {
+                self.highlighted_index = index;
+                self.reset_preview_scroll_unless_pinned();
+                self.notification = Some(format!("Jump: {}", c.to_uppercase()));
+            }
+            None => self.notification = Some(format!("Jump: no template starts with {}.", c.to_uppercase())),
+        }
+    }
+
+    /// Leaves the custom section input without saving.
+    pub fn cancel_custom_section(&mut self) {
+        self.custom_draft = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Handles Enter in the custom section input: accepts the name, adds a line,
+    /// or (on an empty line) saves the section to the config and selects it.
+    pub fn submit_custom_input(&mut self) {
+        let Some(draft) = self.custom_draft.as_mut() else {
+            return;
+        };
+        let input = std::mem::take(&mut draft.input);
+        self.error = None;
+
+        let Some(name) = draft.name.clone() else {
+            let name = input.trim().to_string();
+            if name.is_empty() {
+                self.error = Some(AppError::Input("Enter a name for the custom section.".to_string()));
+            } else if !self.custom_templates.contains(&name) && self.resolve_template_name(&name).is_some() {
+                self.error = Some(AppError::Input(format!("A template named {} already exists.", name)));
+            } else if let Some(draft) = self.custom_draft.as_mut() {
+                draft.name = Some(name);
+            }
+            return;
+        };
+        if !input.trim().is_empty() {
+            draft.lines.push(input.trim_end().to_string());
+            return;
+        }
+        if draft.lines.is_empty() {
+            self.error = Some(AppError::Input("Add at least one line before saving.".to_string()));
+            return;
+        }
+
+        let content = draft.lines.join("\n");
+        self.custom_draft = None;
+        self.input_mode = InputMode::Normal;
+        self.config.custom_sections.insert(name.clone(), content.clone());
+        self.template_contents.insert(name.clone(), content);
+        self.custom_templates.insert(name.clone());
+        if !self.templates.contains(&name) {
+            let mut templates = self.templates.clone();
+            templates.push(name.clone());
+            self.set_templates(templates);
+        }
+        self.select(name.clone());
+        match self.config.save() {
+            Ok(()) => self.notification = Some(format!("Added custom section {}.", name)),
+            Err(e) => self.error = Some(AppError::Config(format!("Failed to save config: {:#}", e))),
+        }
+    }
+
+    /// Turns peeking on or off; turning it on switches to the Combined preview.
+    pub fn toggle_peek(&mut self) {
+        self.peek = !self.peek;
+        if self.peek {
+            self.show_combined_preview();
+        }
+    }
+
+    /// Expands the preview to the whole screen or restores the normal layout.
+    /// The scroll position is kept; drawing re-clamps it to the new preview size.
+    pub fn toggle_preview_zoom(&mut self) {
+        self.preview_zoomed = !self.preview_zoomed;
+    }
+
+    /// The highlighted template shown in the Combined preview while peeking, if it isn't selected.
+    pub fn peeked_template(&self) -> Option<String> {
+        if !self.peek {
+            return None;
+        }
+        self.get_current_highlighted()
+            .filter(|t| !self.selected_templates.contains(t))
+    }
+
+    /// Switches the preview to the Combined view of all selected templates.
+    pub fn show_combined_preview(&mut self) {
+        if self.preview_mode != PreviewMode::Combined {
+            self.preview_mode = PreviewMode::Combined;
+            self.preview_scroll = 0;
+            self.preview_hscroll = 0;
+        }
+    }
+
+    /// Resolves a user-supplied name, alias or API slug to a known template, ignoring case.
+    pub fn resolve_template_name(&self, name: &str) -> Option<String> {
+        self.templates
+            .iter()
+            .find(|t| t.eq_ignore_ascii_case(name))
+            .or_else(|| {
+                self.templates.iter().find(|t| {
+                    aliases::aliases_for(t)
+                        .iter()
+                        .any(|alias| alias.eq_ignore_ascii_case(name))
+                })
+            })
+            .or_else(|| {
+                self.templates
+                    .iter()
+                    .find(|t| self.template_slugs.get(*t).is_some_and(|slug| slug.eq_ignore_ascii_case(name)))
+            })
+            .cloned()
+    }
+
+    /// Closest template name to a misspelled `name`, by fuzzy score in either direction
+    /// (so both `rst` and `rustt` suggest `Rust`).
+    pub fn suggest_template_name(&self, name: &str) -> Option<String> {
+        let name = name.to_lowercase();
+        self.templates
+            .iter()
+            .filter_map(|t| {
+                let lower = t.to_lowercase();
+                let forward = self.matcher.fuzzy_match(&lower, &name);
+                let backward = self.matcher.fuzzy_match(&name, &lower);
+                forward.max(backward).map(|score| (score, t))
+            })
+            .max_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.len().cmp(&a.1.len())))
+            .map(|(_, t)| t.clone())
+    }
+
+    /// Preselects the pending default templates (project file or imported selection), if any.
+    pub fn apply_project_defaults(&mut self) {
+        let Some((source, names)) = self.pending_defaults.take() else {
+            return;
+        };
+        self.invalidate_preview();
+
+        let mut unknown = Vec::new();
+        for name in names {
+            match self.resolve_template_name(&name) {
+                Some(template) => self.select(template),
+                None => unknown.push(name),
+            }
+        }
+
+        if unknown.is_empty() {
+            self.notification = Some(format!("Loaded {}.", source));
+        } else {
+            self.error = Some(AppError::Input(format!(
+                "Loaded {}; unknown templates: {}",
+                source,
+                unknown.join(", ")
+            )));
+        }
+    }
+
+    /// Fetch status of `template`'s content.
+    pub fn fetch_status(&self, template: &str) -> FetchStatus {
+        if self.template_contents.contains_key(template) {
+            return FetchStatus::Loaded;
+        }
+        self.content_status
+            .get(template)
+            .copied()
+            .unwrap_or(FetchStatus::NotFetched)
+    }
+
+    /// Selected templates whose content hasn't been requested yet, as `(name, slug)` pairs.
+    /// Marks the returned names as in-flight.
+    pub fn take_missing_selected_contents(&mut self) -> Vec<(String, String)> {
+        let missing: Vec<String> = self
+            .selected_templates
+            .iter()
+            .filter(|t| self.fetch_status(t) == FetchStatus::NotFetched)
+            .cloned()
+            .collect();
+        self.mark_fetching(missing)
+    }
+
+    /// Whether any template's content fetch has failed.
+    pub fn has_failed_contents(&self) -> bool {
+        self.content_status.values().any(|status| *status == FetchStatus::Failed)
+    }
+
+    /// Templates whose content fetch failed, as `(name, slug)` pairs marked in-flight again for a retry.
+    pub fn take_failed_contents(&mut self) -> Vec<(String, String)> {
+        let failed: Vec<String> = self
+            .content_status
+            .iter()
+            .filter(|(_, status)| **status == FetchStatus::Failed)
+            .map(|(name, _)| name.clone())
+            .collect();
+        self.mark_fetching(failed)
+    }
+
+    /// Sorts `names`, marks them as being fetched and pairs each with its API slug.
+    fn mark_fetching(&mut self, mut names: Vec<String>) -> Vec<(String, String)> {
+        names.sort();
+        if !names.is_empty() {
+            self.invalidate_preview();
+        }
+        for name in &names {
+            self.content_status.insert(name.clone(), FetchStatus::Fetching);
+        }
+        names
+            .into_iter()
+            .map(|name| {
+                let slug = self.slug_for(&name);
+                (name, slug)
+            })
+            .collect()
+    }
+
+    /// API slug `name`'s content is fetched from. Names without a stored slug (e.g. from a cache
+    /// written before slugs were kept) fall back to the lowercased name.
+    pub fn slug_for(&self, name: &str) -> String {
+        self.template_slugs.get(name).cloned().unwrap_or_else(|| name.to_lowercase())
+    }
+
+    /// Placeholder shown in the preview for a template without content.
+    fn missing_content_message(&self, template: &str) -> &'static str {
+        match self.fetch_status(template) {
+            FetchStatus::Fetching => "Loading...",
+            FetchStatus::Failed => "Content unavailable (retry with R).",
+            _ => "Content not cached; select this template to fetch it.",
+        }
+    }
+
+    /// Merges the results of an on-demand content fetch, reporting any `(name, reason)` failures.
+    pub fn apply_fetched_contents(
+        &mut self,
+        contents: HashMap<String, String>,
+        failures: Vec<(String, String)>,
+    ) {
+        self.invalidate_preview();
+        for name in contents.keys() {
+            self.content_status.insert(name.clone(), FetchStatus::Loaded);
+        }
+        for (name, _) in &failures {
+            self.content_status.insert(name.clone(), FetchStatus::Failed);
+        }
+        let loaded_empty = contents
+            .iter()
+            .any(|(name, content)| self.selected_templates.contains(name) && content.trim().is_empty());
+        self.template_contents.extend(contents);
+        if loaded_empty {
+            self.notification = self.empty_selected_notice();
+        }
+
+        if !failures.is_empty() {
+            let mut failures = failures;
+            failures.sort();
+            let reasons: Vec<String> = failures
+                .iter()
+                .map(|(name, reason)| format!("{} ({})", name, reason))
+                .collect();
+            self.error = Some(AppError::Network(format!(
+                "Failed to fetch {} template(s): {}",
+                failures.len(),
+                reasons.join(", ")
+            )));
+        }
     }
 
     pub fn get_current_highlighted(&self) -> Option<String> {
-        self.filtered_templates.get(self.highlighted_index).cloned()
+        self.filtered_name(self.highlighted_index).cloned()
+    }
+
+    /// Name of the template at `position` in the filtered list.
+    pub fn filtered_name(&self, position: usize) -> Option<&String> {
+        self.filtered_templates.get(position).map(|&i| &self.templates[i])
     }
 
+    /// Names of the filtered templates, in display order.
+    pub fn filtered_names(&self) -> impl Iterator<Item = &String> {
+        self.filtered_templates.iter().map(|&i| &self.templates[i])
+    }
+
+    /// Marks the memoized preview stale; call after changing the selection or template contents.
+    pub fn invalidate_preview(&self) {
+        self.preview_cache.borrow_mut().take();
+    }
+
+    /// Preview text for the current view, rebuilt only when its inputs changed.
     pub fn get_combined_preview(&self) -> String {
+        let key = PreviewKey {
+            mode: self.preview_mode,
+            compared: self.compared_pair().map(|(a, b)| (a.to_string(), b.to_string())),
+            shown: self.pinned_preview.clone().or_else(|| self.get_current_highlighted()),
+            peeked: self.peeked_template(),
+            strip_comments: self.strip_comments,
+            trim_whitespace: self.trim_whitespace,
+        };
+        let mut cache = self.preview_cache.borrow_mut();
+        if let Some((cached_key, preview)) = cache.as_ref()
+            && *cached_key == key
+        {
+            return preview.clone();
+        }
+        let preview = self.build_preview();
+        *cache = Some((key, preview.clone()));
+        preview
+    }
+
+    fn build_preview(&self) -> String {
+        if let Some((a, b)) = self.compared_pair() {
+            return self.build_comparison(a, b);
+        }
         match self.preview_mode {
             PreviewMode::Highlighted => {
-                if let Some(t) = self.get_current_highlighted() {
+                if let Some(t) = self
+                    .pinned_preview
+                    .clone()
+                    .or_else(|| self.get_current_highlighted())
+                {
                     let content = self
                         .template_contents
                         .get(&t)
-                        .cloned()
-                        .unwrap_or_else(|| "Loading preview...".to_string());
+                        .map(String::as_str)
+                        .unwrap_or_else(|| self.missing_content_message(&t));
                     format!("--- PREVIEWING: {} ---\n\n{}", t, content)
                 } else {
                     "No template highlighted.".to_string()
                 }
             }
             PreviewMode::Combined => {
-                if self.selected_templates.is_empty() {
+                let peeked = self.peeked_template();
+                if self.selected_templates.is_empty() && peeked.is_none() {
                     return "No templates selected. Use [Highlighted] view to see templates."
                         .to_string();
                 }
 
                 let mut combined = String::new();
-                let mut sorted_selected: Vec<_> = self.selected_templates.iter().collect();
-                sorted_selected.sort();
+                let sorted_selected = self.ordered(self.selected_templates.iter().chain(peeked.as_ref()).collect());
 
                 for t in sorted_selected {
-                    combined.push_str(&format!("### {} ###\n", t));
-                    combined.push_str(
-                        self.template_contents
-                            .get(t)
-                            .map(|s| s.as_str())
-                            .unwrap_or("Loading..."),
-                    );
+                    if peeked.as_ref() == Some(t) {
+                        combined.push_str(&format!("### {} (peeking) ###\n", t));
+                    } else {
+                        combined.push_str(&format!("### {} ###\n", t));
+                    }
+                    match self.template_contents.get(t) {
+                        Some(content) => combined.push_str(&self.clean_content(content)),
+                        None => combined.push_str(self.missing_content_message(t)),
+                    }
                     combined.push_str("\n\n");
                 }
-                combined
+                truncate_preview(combined)
             }
         }
     }
 
+    /// Both compared templates, once the second one has been chosen.
+    pub fn compared_pair(&self) -> Option<(&str, &str)> {
+        match &self.comparison {
+            Some((a, Some(b))) => Some((a.as_str(), b.as_str())),
+            _ => None,
+        }
+    }
+
+    /// Marks the highlighted template for comparison: the first press picks A, the second B
+    /// (showing the diff), and a press while a diff is shown ends the comparison.
+    pub fn mark_compare(&mut self) {
+        self.error = None;
+        if self.compared_pair().is_some() {
+            self.clear_compare();
+            return;
+        }
+        let Some(template) = self.get_current_highlighted() else {
+            return;
+        };
+        self.preview_scroll = 0;
+        self.comparison = match self.comparison.take() {
+            None => {
+                self.notification = Some(format!("Comparing {}: highlight another template and press C.", template));
+                Some((template, None))
+            }
+            Some((first, None)) if first == template => {
+                self.notification = Some("Highlight a different template to compare with.".to_string());
+                Some((first, None))
+            }
+            Some((first, _)) => {
+                self.notification = Some(format!("Comparing {} with {}; C or Esc to stop.", first, template));
+                Some((first, Some(template)))
+            }
+        };
+    }
+
+    /// Ends the comparison and returns the preview to its normal view.
+    pub fn clear_compare(&mut self) {
+        self.comparison = None;
+        self.preview_scroll = 0;
+        self.notification = Some("Comparison closed.".to_string());
+    }
+
+    /// Unified diff from template `a` to template `b`, after the usual output clean-ups.
+    fn build_comparison(&self, a: &str, b: &str) -> String {
+        let header = format!("--- COMPARING: {} vs {} ---\n\n", a, b);
+        let (Some(old), Some(new)) = (self.template_contents.get(a), self.template_contents.get(b)) else {
+            let missing = if self.template_contents.contains_key(a) { b } else { a };
+            return header + self.missing_content_message(missing);
+        };
+        let old = self.clean_content(old) + "\n";
+        let new = self.clean_content(new) + "\n";
+        match gitignore::labeled_diff(a, &old, b, &new) {
+            Some(diff) => truncate_preview(header + &diff),
+            None => header + "The two templates are identical.",
+        }
+    }
+
+    /// Number of rows the preview occupies, accounting for wrapping when enabled.
     pub fn get_preview_line_count(&self) -> usize {
-        self.get_combined_preview().lines().count()
+        let preview = self.get_combined_preview();
+        let width = self.preview_width as usize;
+        if !self.preview_wrap || width == 0 {
+            return preview.lines().count();
+        }
+        preview
+            .lines()
+            .map(|line| line.width().max(1).div_ceil(width))
+            .sum()
     }
 
     pub fn max_preview_scroll(&self) -> u16 {
@@ -205,7 +1377,23 @@ impl App {
         max_scroll.min(u16::MAX as usize) as u16
     }
 
-    pub fn set_preview_height(&mut self, height: u16) {
+    /// Furthest horizontal offset that still shows part of the longest line.
+    pub fn max_preview_hscroll(&self) -> u16 {
+        if self.preview_wrap {
+            return 0;
+        }
+        let longest = self
+            .get_combined_preview()
+            .lines()
+            .map(|line| line.width())
+            .max()
+            .unwrap_or(0);
+        let max_scroll = longest.saturating_sub(self.preview_width as usize);
+        max_scroll.min(u16::MAX as usize) as u16
+    }
+
+    pub fn set_preview_size(&mut self, width: u16, height: u16) {
+        self.preview_width = width;
         self.preview_height = height;
         self.clamp_preview_scroll();
     }
@@ -215,33 +1403,541 @@ impl App {
         if self.preview_scroll > max_scroll {
             self.preview_scroll = max_scroll;
         }
+        let max_hscroll = self.max_preview_hscroll();
+        if self.preview_hscroll > max_hscroll {
+            self.preview_hscroll = max_hscroll;
+        }
+    }
+
+    /// Switches between wrapped and horizontally scrollable preview lines.
+    pub fn toggle_preview_wrap(&mut self) {
+        self.preview_wrap = !self.preview_wrap;
+        self.preview_hscroll = 0;
+        self.clamp_preview_scroll();
     }
 
     pub fn generate_gitignore_content(&self) -> String {
-        let mut sorted_selected: Vec<_> = self.selected_templates.iter().collect();
-        sorted_selected.sort();
+        let sorted_selected = self.ordered(self.selected_templates.iter().collect());
+        if self.flat {
+            let names: Vec<&str> = sorted_selected.iter().map(|t| t.as_str()).collect();
+            let cleaned: Vec<String> = sorted_selected
+                .iter()
+                .map(|t| self.clean_content(self.template_contents.get(*t).map(|s| s.as_str()).unwrap_or("")))
+                .collect();
+            let patterns = gitignore::flatten(cleaned.iter().map(String::as_str));
+            return format!("\n# Templates: {}\n{}\n", names.join(", "), patterns.join("\n"));
+        }
 
         let mut combined = String::new();
         for t in sorted_selected {
-            combined.push_str(&format!("\n# --- {} ---\n", t));
-            combined.push_str(self.template_contents.get(t).map(|s| s.as_str()).unwrap_or(""));
+            let content = self.template_contents.get(t).map(|s| s.as_str());
+            combined.push('\n');
+            if !self.config.section_header.is_empty() {
+                combined.push_str(&gitignore::section_header(&self.config.section_header, t));
+                combined.push('\n');
+            }
+            let cleaned = self.clean_content(content.unwrap_or(""));
+            if cleaned.is_empty() && content.is_some() {
+                // Say why the section is blank instead of leaving a bare header.
+                combined.push_str(EMPTY_TEMPLATE_NOTE);
+            } else {
+                combined.push_str(&cleaned);
+            }
             combined.push('\n');
         }
         combined
     }
 
-    pub fn get_selected_names_summary(&self) -> String {
-        let mut selected: Vec<_> = self.selected_templates.iter().collect();
+    /// Selected templates (in output order) whose loaded content is empty or whitespace-only.
+    pub fn empty_selected(&self) -> Vec<String> {
+        self.ordered(self.selected_templates.iter().collect())
+            .into_iter()
+            .filter(|t| self.template_contents.get(*t).is_some_and(|c| c.trim().is_empty()))
+            .cloned()
+            .collect()
+    }
+
+    /// Notice for selected templates that contribute no patterns, if there are any.
+    pub fn empty_selected_notice(&self) -> Option<String> {
+        match self.empty_selected().as_slice() {
+            [] => None,
+            [name] => Some(format!("{} is an empty template and adds nothing.", name)),
+            names => Some(format!("{} selected templates are empty and add nothing: {}", names.len(), names.join(", "))),
+        }
+    }
+
+    /// Applies the output clean-ups (comment stripping, whitespace trimming) to template content.
+    /// Leading and trailing blank lines are always dropped so section spacing stays uniform.
+    fn clean_content(&self, content: &str) -> String {
+        let content = if self.trim_whitespace {
+            gitignore::trim_trailing_whitespace(content)
+        } else {
+            content.to_string()
+        };
+        let content = if self.strip_comments {
+            gitignore::strip_comments(&content)
+        } else {
+            content
+        };
+        gitignore::trim_blank_lines(&content).to_string()
+    }
+
+    /// Selected template names in alphabetical order.
+    pub fn get_selected_names(&self) -> Vec<String> {
+        let mut selected: Vec<String> = self.selected_templates.iter().cloned().collect();
         selected.sort();
-        selected.into_iter().cloned().collect::<Vec<_>>().join(", ")
+        selected
     }
 
+    /// Target .gitignore path in every output directory.
+    pub fn gitignore_paths(&self) -> Vec<PathBuf> {
+        self.output_dirs.iter().map(|dir| dir.join(".gitignore")).collect()
+    }
 
-    pub fn gitignore_path(&self) -> PathBuf {
-        self.output_dir.join(".gitignore")
+    /// Target .gitignore paths for display, relative to the cwd when they are inside it.
+    pub fn gitignore_display_paths(&self) -> Vec<String> {
+        let cwd = std::env::current_dir().ok();
+        self.gitignore_paths()
+            .iter()
+            .map(|path| {
+                cwd.as_deref()
+                    .and_then(|cwd| path.strip_prefix(cwd).ok())
+                    .unwrap_or(path)
+                    .display()
+                    .to_string()
+            })
+            .collect()
     }
 
+    /// Whether any of the target directories already has a .gitignore.
     pub fn gitignore_exists(&self) -> bool {
-        self.gitignore_path().exists()
+        self.gitignore_paths().iter().any(|path| path.exists())
+    }
+
+    /// Shows or hides the append preview, scrolled to just before the first changed line.
+    pub fn toggle_append_preview(&mut self) {
+        if self.append_preview.take().is_some() {
+            return;
+        }
+        let (preview, first_change) = self.build_append_preview();
+        self.append_preview_scroll = first_change.saturating_sub(3).min(u16::MAX as usize) as u16;
+        self.append_preview = Some(preview);
+    }
+
+    /// Scrolls the append preview by `delta` lines, staying within its content.
+    pub fn scroll_append_preview(&mut self, delta: i32) {
+        let Some(preview) = &self.append_preview else {
+            return;
+        };
+        let max = preview.lines().count().saturating_sub(1).min(u16::MAX as usize) as u16;
+        self.append_preview_scroll = self.append_preview_scroll.saturating_add_signed(delta as i16).min(max);
+    }
+
+    /// What the first target's .gitignore would contain after an append, via the same merge as the
+    /// real write, plus the index of the first line that differs from the current file.
+    fn build_append_preview(&self) -> (String, usize) {
+        let path = &self.gitignore_paths()[0];
+        let existing = match std::fs::read(path) {
+            Ok(bytes) => match String::from_utf8(bytes) {
+                Ok(text) => LineEnding::Lf.apply(&text),
+                Err(_) => {
+                    return ("Existing file is not valid UTF-8; it would be overwritten instead.".to_string(), 0);
+                }
+            },
+            Err(e) => return (format!("Can't read {}: {}", path.display(), e), 0),
+        };
+        let merged = gitignore::merge_append(
+            &existing,
+            &LineEnding::Lf.apply(&self.generate_gitignore_content()),
+            &self.config.section_header,
+        );
+        let first_change = existing
+            .lines()
+            .zip(merged.lines())
+            .take_while(|(old, new)| old == new)
+            .count();
+        (merged, first_change)
+    }
+
+    /// The most lines an Overwrite would remove from any existing target, if that exceeds
+    /// the configured warning threshold. Unreadable or non-UTF-8 files are not counted.
+    pub fn overwrite_removals_over_threshold(&self) -> Option<usize> {
+        let threshold = self.config.overwrite_warning.filter(|limit| *limit > 0)?;
+        let new = LineEnding::Lf.apply(&self.generate_gitignore_content());
+        let removed = self
+            .gitignore_paths()
+            .iter()
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .map(|existing| gitignore::removed_lines(&LineEnding::Lf.apply(&existing), &new))
+            .max()?;
+        (removed > threshold).then_some(removed)
+    }
+
+    /// Writes the generated content to the .gitignore in every output directory,
+    /// returning each target path with its own result.
+    /// With `write_gitattributes`, a standard .gitattributes is written next to each one. That file
+    /// is always appended to, whatever `mode` is: its section is replaced in place and any other
+    /// rules are kept, so an existing .gitattributes is never overwritten without being asked about.
+    /// Successful .gitignore writes are recorded in the audit log and, with `record_stats`,
+    /// in the usage stats (best effort).
+    pub fn save_each(&self, mode: WriteMode) -> Vec<(PathBuf, Result<WriteOutcome>)> {
+        let content = self.generate_gitignore_content();
+        let names = self.get_selected_names();
+        let mut results = Vec::new();
+        for dir in &self.output_dirs {
+            let path = dir.join(".gitignore");
+            let result = self.save_to(dir, &path, &content, mode, &self.config.section_header);
+            if result.is_ok() {
+                let _ = audit::record(&path, &names);
+            }
+            results.push((path, result));
+
+            if self.write_gitattributes {
+                let path = dir.join(".gitattributes");
+                let result = self.save_to(
+                    dir,
+                    &path,
+                    gitignore::GITATTRIBUTES_CONTENT,
+                    WriteMode::Append,
+                    gitignore::DEFAULT_SECTION_HEADER,
+                );
+                results.push((path, result));
+            }
+        }
+        if self.config.record_stats && results.iter().any(|(_, result)| result.is_ok()) {
+            let _ = stats::record(&names);
+        }
+        results
+    }
+
+    /// Records the first output directory that received one of the `written` files as the
+    /// default for the next run, if that is enabled.
+    pub fn remember_output_dir(&mut self, written: &[PathBuf]) -> Result<()> {
+        if !self.config.remember_output_dir {
+            return Ok(());
+        }
+        let Some(dir) = self
+            .output_dirs
+            .iter()
+            .find(|dir| written.iter().any(|path| path.parent() == Some(dir.as_path())))
+            .cloned()
+        else {
+            return Ok(());
+        };
+        if self.config.last_output_dir.as_ref() == Some(&dir) {
+            return Ok(());
+        }
+        self.config.last_output_dir = Some(dir);
+        self.config.save()
+    }
+
+    /// Unified diff of what a write in `mode` would change in each target .gitignore
+    /// (`None` when a target is already up to date). Nothing is written.
+    pub fn diff_each(&self, mode: WriteMode) -> Vec<(PathBuf, Result<Option<String>>)> {
+        let content = self.generate_gitignore_content();
+        self.gitignore_paths()
+            .into_iter()
+            .map(|path| {
+                let result = gitignore::planned_content(&path, &content, mode, self.eol, &self.config.section_header)
+                    .and_then(|(planned, _)| {
+                        let existing = match std::fs::read(&path) {
+                            Ok(bytes) => Some(String::from_utf8_lossy(&bytes).into_owned()),
+                            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                            Err(e) => return Err(anyhow::Error::new(e).context(format!("Failed to read {}", path.display()))),
+                        };
+                        Ok(gitignore::unified_diff(&path, existing.as_deref(), &planned))
+                    });
+                (path, result)
+            })
+            .collect()
+    }
+
+    /// Writes every target, collecting the written paths (with what each write did) and the
+    /// failures separately, so one failing directory doesn't hide the others.
+    pub fn save(&self, mode: WriteMode) -> SaveReport {
+        let mut report = SaveReport::default();
+        for (path, result) in self.save_each(mode) {
+            match result {
+                Ok(outcome) => report.written.push((path, outcome)),
+                Err(e) => report.failures.push(format!("{}: {:#}", path.display(), e)),
+            }
+        }
+        report
+    }
+
+    /// Records a summary line for each file in `written`: its path, resulting line count,
+    /// and the templates it received.
+    pub fn record_written(&mut self, written: &[(PathBuf, WriteOutcome)]) {
+        let templates = self.ordered(self.selected_templates.iter().collect());
+        let templates: Vec<&str> = templates.iter().map(|t| t.as_str()).collect();
+        for (path, _) in written {
+            let lines = std::fs::read_to_string(path).map(|c| c.lines().count()).unwrap_or(0);
+            self.write_summary.push(format!(
+                "Wrote {} ({} lines): {}",
+                path.display(),
+                lines,
+                templates.join(", ")
+            ));
+        }
+    }
+
+    /// Writes a single target, refusing paths that resolve outside `dir` unless explicitly allowed.
+    fn save_to(
+        &self,
+        dir: &Path,
+        path: &Path,
+        content: &str,
+        mode: WriteMode,
+        header_template: &str,
+    ) -> Result<WriteOutcome> {
+        if self.read_only {
+            return Err(anyhow::anyhow!("Read-only mode: writing is disabled"));
+        }
+        if !self.allow_outside {
+            gitignore::ensure_within(dir, path)?;
+        }
+        if self.config.never_overwrite && path.exists() {
+            let (_, outcome) = gitignore::planned_content(path, content, mode, self.eol, header_template)?;
+            if mode == WriteMode::Overwrite || outcome.overwrote_unreadable {
+                return Err(anyhow::anyhow!(
+                    "{} would be overwritten, which never_overwrite forbids",
+                    path.display()
+                ));
+            }
+        }
+        gitignore::write_gitignore(path, content, mode, self.eol, header_template)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// App loaded with `templates` (each with a one-line body) and the given `(name, slug)` pairs.
+    fn app_with(templates: &[&str], slugs: &[(&str, &str)]) -> App {
+        let mut app = App::new(&Args::default(), Config::default());
+        app.load_data(CacheData {
+            templates: templates.iter().map(|t| t.to_string()).collect(),
+            contents: templates.iter().map(|t| (t.to_string(), format!("{}.out", t))).collect(),
+            last_used: HashMap::new(),
+            slugs: slugs.iter().map(|(name, slug)| (name.to_string(), slug.to_string())).collect(),
+            oversized: Vec::new(),
+        });
+        app
+    }
+
+    /// Names listed after searching `app` for `query`.
+    fn search(app: &mut App, query: &str) -> Vec<String> {
+        app.search_query = query.to_string();
+        app.apply_filter();
+        app.filtered_templates.iter().map(|&i| app.templates[i].clone()).collect()
+    }
+
+    #[test]
+    fn equal_scores_prefer_prefix_then_shorter_then_name() {
+        use std::cmp::Ordering;
+        assert_eq!(compare_matches("go", (10, "Godot", "godot"), (10, "Django", "django")), Ordering::Less);
+        assert_eq!(compare_matches("go", (10, "Godot", "godot"), (10, "Gold", "gold")), Ordering::Greater);
+        assert_eq!(compare_matches("go", (10, "Goat", "goat"), (10, "Gold", "gold")), Ordering::Less);
+        // A better fuzzy score still wins over a prefix.
+        assert_eq!(compare_matches("go", (20, "Django", "django"), (10, "Godot", "godot")), Ordering::Less);
+    }
+
+    #[test]
+    fn fuzzy_ties_put_the_prefix_match_first() {
+        let mut app = app_with(&["Django", "Go", "Godot", "Mongo"], &[]);
+        let results = search(&mut app, "go");
+        assert_eq!(results[0], "Go");
+        assert!(results.iter().position(|t| t == "Godot") < results.iter().position(|t| t == "Django"), "{results:?}");
+    }
+
+    #[test]
+    fn aliases_rank_their_template_first() {
+        let mut app = app_with(&["Kubernetes", "Knockout", "TypeScript", "Tasm", "Python"], &[]);
+        assert_eq!(search(&mut app, "k8s").first().map(String::as_str), Some("Kubernetes"));
+        assert_eq!(search(&mut app, "ts").first().map(String::as_str), Some("TypeScript"));
+        assert_eq!(search(&mut app, "py").first().map(String::as_str), Some("Python"));
+    }
+
+    #[test]
+    fn search_order_does_not_depend_on_load_order() {
+        let names = ["Go", "Django", "Godot", "Mongo", "Gradle", "Hugo"];
+        let reversed: Vec<&str> = names.iter().rev().copied().collect();
+        let mut app = app_with(&names, &[]);
+        let mut other = app_with(&reversed, &[]);
+        for mode in [SearchMode::Fuzzy, SearchMode::Substring, SearchMode::Prefix] {
+            app.search_mode = mode;
+            other.search_mode = mode;
+            let results = search(&mut app, "go");
+            assert_eq!(search(&mut other, "go"), results, "{mode:?}");
+            assert_eq!(search(&mut app, "go"), results, "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn sections_are_trimmed_of_surrounding_blank_lines() {
+        let mut app = app_with(&["Go", "Rust"], &[]);
+        app.template_contents.insert("Go".to_string(), "\n\n*.exe\n\n".to_string());
+        app.template_contents.insert("Rust".to_string(), "target/\n\n\n".to_string());
+        app.selected_templates = ["Go", "Rust"].iter().map(|t| t.to_string()).collect();
+        assert_eq!(app.generate_gitignore_content(), "\n# --- Go ---\n*.exe\n\n# --- Rust ---\ntarget/\n");
+    }
+
+    #[test]
+    fn preview_is_rebuilt_when_its_inputs_change() {
+        let mut app = app_with(&["Go", "Rust"], &[]);
+        app.preview_mode = PreviewMode::Combined;
+        app.search_query.clear();
+        app.apply_filter();
+        app.highlighted_index = 0;
+        app.toggle_selection();
+        let preview = app.get_combined_preview();
+        assert!(preview.contains("### Go ###") && !preview.contains("### Rust ###"), "{preview}");
+        assert_eq!(app.get_combined_preview(), preview);
+
+        app.highlighted_index = 1;
+        app.toggle_selection();
+        assert!(app.get_combined_preview().contains("### Rust ###"));
+
+        app.apply_fetched_contents(HashMap::from([("Go".to_string(), "# bin\n*.exe".to_string())]), Vec::new());
+        assert!(app.get_combined_preview().contains("# bin\n*.exe"));
+        app.strip_comments = true;
+        assert!(!app.get_combined_preview().contains("# bin"));
+    }
+
+    #[test]
+    fn exact_name_ranks_first_and_is_highlighted() {
+        let mut app = app_with(&["Ansible", "Node", "NotepadPP", "Nodemon", "Yeoman"], &[]);
+        app.highlighted_index = 3;
+        let results = search(&mut app, "node");
+        assert_eq!(results[0], "Node");
+        assert_eq!(app.highlighted_index, 0);
+
+        // Without an exact match the cursor stays put.
+        app.highlighted_index = 1;
+        search(&mut app, "nod");
+        assert_eq!(app.highlighted_index, 1);
+    }
+
+    #[test]
+    fn flat_output_lists_templates_then_patterns() {
+        let mut app = app_with(&["Go", "Rust"], &[]);
+        app.flat = true;
+        app.template_contents.insert("Rust".to_string(), "target/\n*.out\n".to_string());
+        app.selected_templates = ["Go", "Rust"].iter().map(|t| t.to_string()).collect();
+        assert_eq!(app.generate_gitignore_content(), "\n# Templates: Go, Rust\n*.out\nGo.out\ntarget/\n");
+    }
+
+    #[test]
+    fn empty_templates_are_noted_in_the_output_and_reported() {
+        let mut app = app_with(&["Go", "Empty", "Blank"], &[]);
+        app.template_contents.insert("Empty".to_string(), String::new());
+        app.template_contents.insert("Blank".to_string(), "\n  \n".to_string());
+        app.selected_templates = ["Go", "Empty"].iter().map(|t| t.to_string()).collect();
+        assert_eq!(
+            app.generate_gitignore_content(),
+            format!("\n# --- Empty ---\n{}\n\n# --- Go ---\nGo.out\n", EMPTY_TEMPLATE_NOTE)
+        );
+        assert_eq!(app.empty_selected_notice().as_deref(), Some("Empty is an empty template and adds nothing."));
+
+        app.selected_templates.insert("Blank".to_string());
+        assert_eq!(
+            app.empty_selected_notice().as_deref(),
+            Some("2 selected templates are empty and add nothing: Blank, Empty")
+        );
+        app.selected_templates = HashSet::from(["Go".to_string()]);
+        assert_eq!(app.empty_selected_notice(), None);
+    }
+
+    #[test]
+    fn prefix_search_lists_name_prefixes_alphabetically() {
+        let mut app = app_with(&["Django", "Gradle", "Go", "Godot", "GitBook"], &[]);
+        app.search_query = "go".to_string();
+        app.toggle_prefix_search();
+        assert_eq!(app.search_mode, SearchMode::Prefix);
+        assert_eq!(search(&mut app, "go"), ["Go", "Godot"]);
+        assert_eq!(search(&mut app, "G"), ["GitBook", "Go", "Godot", "Gradle"]);
+
+        app.toggle_prefix_search();
+        assert_eq!(app.search_mode, SearchMode::Fuzzy);
+        assert!(search(&mut app, "go").contains(&"Django".to_string()));
+    }
+
+    #[test]
+    fn jump_cycles_matches_and_keeps_a_pinned_preview_scrolled() {
+        let mut app = app_with(&["Go", "Godot", "Rust", "Ruby"], &[]);
+        app.apply_filter();
+        app.jump_to_letter('r');
+        assert_eq!(app.get_current_highlighted().as_deref(), Some("Ruby"));
+        app.jump_to_letter('R');
+        assert_eq!(app.get_current_highlighted().as_deref(), Some("Rust"));
+
+        app.preview_scroll = 5;
+        app.jump_to_letter('g');
+        assert_eq!(app.preview_scroll, 0);
+
+        app.pinned_preview = Some("Go".to_string());
+        app.preview_scroll = 5;
+        app.jump_to_letter('r');
+        assert_eq!(app.preview_scroll, 5);
+    }
+
+    #[test]
+    fn diff_each_reports_only_targets_that_would_change() {
+        let current = tempfile::tempdir().unwrap();
+        let missing = tempfile::tempdir().unwrap();
+        let mut app = app_with(&["Go"], &[]);
+        app.selected_templates.insert("Go".to_string());
+        app.output_dirs = vec![current.path().to_path_buf(), missing.path().to_path_buf()];
+        let fresh = gitignore::fresh_content(".gitignore", &app.generate_gitignore_content());
+        std::fs::write(current.path().join(".gitignore"), &fresh).unwrap();
+
+        let diffs = app.diff_each(WriteMode::Overwrite);
+        assert_eq!(diffs[0].1.as_ref().unwrap(), &None);
+        let created = diffs[1].1.as_ref().unwrap().as_deref().unwrap();
+        assert!(created.starts_with("--- /dev/null"), "{created}");
+        assert!(created.contains("+Go.out"), "{created}");
+        assert!(!missing.path().join(".gitignore").exists());
+    }
+
+    #[test]
+    fn new_badge_clears_only_for_the_shown_template() {
+        let mut app = app_with(&["Go", "Rust"], &[]);
+        app.new_templates = HashSet::from(["Go".to_string(), "Rust".to_string()]);
+        app.apply_filter();
+        app.preview_mode = PreviewMode::Combined;
+        app.mark_shown_seen();
+        assert_eq!(app.new_templates.len(), 2);
+
+        app.preview_mode = PreviewMode::Highlighted;
+        app.mark_shown_seen();
+        assert_eq!(app.new_templates, HashSet::from(["Rust".to_string()]));
+
+        app.pinned_preview = Some("Go".to_string());
+        app.next();
+        app.mark_shown_seen();
+        assert!(app.new_templates.contains("Rust"));
+    }
+
+    #[test]
+    fn slug_resolves_to_its_template_and_is_used_for_fetching() {
+        let app = app_with(&["Visual Studio Code", "Rust"], &[("Visual Studio Code", "visualstudiocode")]);
+        assert_eq!(app.resolve_template_name("visualstudiocode").as_deref(), Some("Visual Studio Code"));
+        assert_eq!(app.slug_for("Visual Studio Code"), "visualstudiocode");
+        // Without a stored slug the lowercased name is the best guess.
+        assert_eq!(app.slug_for("Rust"), "rust");
+    }
+
+    #[test]
+    fn save_report_names_failures_and_successes() {
+        let mut report = SaveReport::default();
+        assert_eq!(report.error(), None);
+        report.written.push((PathBuf::from("a/.gitignore"), WriteOutcome::default()));
+        report.failures.push("b/.gitignore: Target directory does not exist: b".to_string());
+
+        let error = report.error().unwrap();
+        assert!(error.starts_with("Failed to write 1 target(s): b/.gitignore"), "{error}");
+        assert!(error.ends_with("Wrote a/.gitignore"), "{error}");
+        assert_eq!(report.written_paths(), [PathBuf::from("a/.gitignore")]);
     }
 }
@@ -0,0 +1,89 @@
+use anyhow::{Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::project_dirs;
+
+/// Oldest entries are dropped once the log grows past this many lines.
+const MAX_AUDIT_ENTRIES: usize = 1000;
+
+/// Location of the write log in the OS data directory.
+pub fn path() -> Result<PathBuf> {
+    Ok(project_dirs()?.data_dir().join("audit.log"))
+}
+
+/// Appends a `timestamp<TAB>path<TAB>templates` line for a completed write, trimming old entries.
+pub fn record(written: &Path, templates: &[String]) -> Result<()> {
+    let log_path = path()?;
+    if let Some(dir) = log_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let entry = format!(
+        "{}\t{}\t{}\n",
+        utc_timestamp(SystemTime::now()),
+        written.display(),
+        templates.join(",")
+    );
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .and_then(|mut file| file.write_all(entry.as_bytes()))
+        .with_context(|| format!("Failed to write audit log {}", log_path.display()))?;
+
+    let content = fs::read_to_string(&log_path)?;
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.len() > MAX_AUDIT_ENTRIES {
+        let kept = lines[lines.len() - MAX_AUDIT_ENTRIES..].join("\n");
+        fs::write(&log_path, kept + "\n")?;
+    }
+    Ok(())
+}
+
+/// Prints the write log (`--audit-log`).
+pub fn print() -> Result<()> {
+    let log_path = path()?;
+    match fs::read_to_string(&log_path) {
+        Ok(content) if !content.is_empty() => print!("{}", content),
+        Ok(_) => println!("No writes recorded yet ({})", log_path.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("No writes recorded yet ({})", log_path.display())
+        }
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", log_path.display()));
+        }
+    }
+    Ok(())
+}
+
+/// Formats `time` as an RFC 3339 UTC timestamp with second precision.
+fn utc_timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    let (year, month, day) = civil_from_days(days as i64);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/// Converts days since 1970-01-01 to a (year, month, day) civil date.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
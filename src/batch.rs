@@ -0,0 +1,188 @@
+use anyhow::Result;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::api::{self, ApiClient};
+use crate::app::App;
+use crate::cli::{Args, usage_error};
+use crate::config::Config;
+use crate::gitignore::{self, WriteMode};
+use crate::local;
+use crate::recipe::Recipe;
+
+/// Generates the requested templates without starting the TUI, writing into every output directory
+/// (or printing the result with `--stdout`, the changes with `--diff`, or saving the selection
+/// with `--export-selection`).
+/// Existing files are appended to (or overwritten with `--overwrite`), with a backup;
+/// per-directory results are printed.
+pub async fn run(args: &Args, config: Config, client: &ApiClient) -> Result<()> {
+    let cache = match client.load_cache().filter(|_| !args.refresh) {
+        Some(cache) => cache,
+        None => {
+            let cache = client.fetch_all_data().await?;
+            if let Some(notice) = api::oversized_notice(&cache) {
+                eprintln!("Warning: {}", notice);
+            }
+            if let Err(e) = client.save_cache(&cache) {
+                eprintln!("Warning: couldn't save the template cache: {:#}", e);
+            }
+            cache
+        }
+    };
+
+    let mut app = App::new(args, config);
+    if let Some(dir) = &args.template_dir {
+        app.local_template_contents = local::load_template_dir(dir)?;
+    }
+    app.load_data(cache);
+
+    let mut unknown = Vec::new();
+    let mut urls = Vec::new();
+    let mut recipe = Recipe::default();
+    for name in &args.generate {
+        if api::is_template_url(name) {
+            urls.push(name);
+            recipe.templates.push(name.clone());
+            continue;
+        }
+        match app.resolve_template_name(name) {
+            Some(template) => {
+                if app.custom_templates.contains(&template)
+                    && let Some(content) = app.template_contents.get(&template)
+                {
+                    recipe.custom_sections.insert(template.clone(), content.clone());
+                }
+                recipe.templates.push(template.clone());
+                app.select(template);
+            }
+            None => unknown.push(match app.suggest_template_name(name) {
+                Some(suggestion) => format!("{} (did you mean '{}'?)", name, suggestion),
+                None => name.clone(),
+            }),
+        }
+    }
+    if !unknown.is_empty() {
+        return Err(usage_error!("Unknown template(s): {}", unknown.join(", ")));
+    }
+
+    if let Some(path) = &args.export_selection {
+        let mut seen = std::collections::HashSet::new();
+        recipe.templates.retain(|name| seen.insert(name.clone()));
+        recipe.save(path)?;
+        if path.as_os_str() != "-" && !args.quiet {
+            println!("Saved the selection to {}", path.display());
+        }
+        return Ok(());
+    }
+
+    for url in urls {
+        let (name, content) = client
+            .fetch_url_template(url)
+            .await
+            .map_err(|e| e.context(format!("Failed to fetch {}", url)))?;
+        app.template_contents.insert(name.clone(), content);
+        app.select(name);
+    }
+
+    let missing = app.take_missing_selected_contents();
+    if !missing.is_empty() {
+        let mut fetched = std::collections::HashMap::new();
+        for (name, result) in client.fetch_templates(&missing).await {
+            fetched.insert(name, result?);
+        }
+        if let Err(e) = client.store_contents(&fetched) {
+            eprintln!("Warning: couldn't save the template cache: {:#}", e);
+        }
+        app.template_contents.extend(fetched);
+    }
+    if let Some(notice) = app.empty_selected_notice() {
+        eprintln!("Warning: {}", notice);
+    }
+
+    if args.stdout {
+        let content = gitignore::fresh_content(".gitignore", &app.generate_gitignore_content());
+        print!("{}", app.eol.apply(&content));
+        let _ = client.record_use(&app.get_selected_names());
+        return Ok(());
+    }
+
+    if args.diff {
+        return print_diffs(&app, args.write_mode.unwrap_or(WriteMode::Append));
+    }
+
+    let results = app.save_each(args.write_mode.unwrap_or(WriteMode::Append));
+    let written: Vec<PathBuf> = results
+        .iter()
+        .filter(|(_, result)| result.is_ok())
+        .map(|(path, _)| path.clone())
+        .collect();
+    if !written.is_empty() {
+        let _ = client.record_use(&app.get_selected_names());
+        let _ = app.remember_output_dir(&written);
+    }
+    let total = results.len();
+    let mut failed = 0;
+    for (path, result) in results {
+        match result {
+            Ok(outcome) => {
+                if !args.quiet {
+                    println!("Wrote {}", path.display());
+                }
+                if let Some(notice) = outcome.notice(&path) {
+                    eprintln!("Warning: {}", notice);
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("Failed to write {}: {:#}", path.display(), e);
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(anyhow::anyhow!(
+            "{} of {} targets failed",
+            failed,
+            total
+        ));
+    }
+    Ok(())
+}
+
+/// `--diff` found targets that writing would change. Not a failure as such: it gets its own exit
+/// code so CI can tell "out of date" from an error.
+#[derive(Debug)]
+pub struct ChangesPending {
+    pub changed: usize,
+    pub total: usize,
+}
+
+impl fmt::Display for ChangesPending {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {} .gitignore file(s) would change", self.changed, self.total)
+    }
+}
+
+impl std::error::Error for ChangesPending {}
+
+/// Prints what writing in `mode` would change in each target, returning `ChangesPending` if
+/// anything would change (so `--diff` can check in CI that a .gitignore is up to date).
+fn print_diffs(app: &App, mode: WriteMode) -> Result<()> {
+    let results = app.diff_each(mode);
+    let total = results.len();
+    let mut changed = 0;
+    for (path, result) in results {
+        match result? {
+            Some(diff) => {
+                changed += 1;
+                print!("{}", diff);
+            }
+            None => eprintln!("{} is up to date", path.display()),
+        }
+    }
+
+    if changed > 0 {
+        return Err(ChangesPending { changed, total }.into());
+    }
+    Ok(())
+}
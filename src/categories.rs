@@ -0,0 +1,74 @@
+/// Broad template kinds, declared in the order their sections are written when grouping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    /// Languages, frameworks, and tools (anything not listed below).
+    Language,
+    /// Editors and IDEs.
+    Editor,
+    /// Operating systems.
+    Os,
+}
+
+/// Editor and IDE templates, by lowercase template name.
+const EDITORS: &[&str] = &[
+    "visualstudiocode",
+    "visualstudio",
+    "jetbrains",
+    "jetbrains+all",
+    "jetbrains+iml",
+    "vim",
+    "emacs",
+    "sublimetext",
+    "atom",
+    "eclipse",
+    "netbeans",
+    "xcode",
+    "androidstudio",
+    "intellij",
+    "intellij+all",
+    "intellij+iml",
+    "pycharm",
+    "pycharm+all",
+    "webstorm",
+    "clion",
+    "rider",
+    "kate",
+    "nova",
+    "zed",
+];
+
+/// Operating system templates, by lowercase template name.
+const OPERATING_SYSTEMS: &[&str] = &["macos", "windows", "linux", "freebsd", "solaris", "osx"];
+
+/// Category of `template` (case-insensitive); unknown names count as languages.
+pub fn category_for(template: &str) -> Category {
+    let name = template.to_ascii_lowercase();
+    if OPERATING_SYSTEMS.contains(&name.as_str()) {
+        Category::Os
+    } else if EDITORS.contains(&name.as_str()) {
+        Category::Editor
+    } else {
+        Category::Language
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_map_to_their_category() {
+        assert_eq!(category_for("macOS"), Category::Os);
+        assert_eq!(category_for("VisualStudioCode"), Category::Editor);
+        assert_eq!(category_for("JetBrains+all"), Category::Editor);
+        assert_eq!(category_for("Rust"), Category::Language);
+        assert_eq!(category_for("SomethingNew"), Category::Language);
+    }
+
+    #[test]
+    fn categories_sort_languages_first_and_os_last() {
+        let mut names = ["Windows", "Vim", "Rust", "macOS", "Go", "Emacs"];
+        names.sort_by_key(|t| (category_for(t), *t));
+        assert_eq!(names, ["Go", "Rust", "Emacs", "Vim", "Windows", "macOS"]);
+    }
+}
@@ -0,0 +1,404 @@
+use anyhow::{Context, Result};
+use std::fmt;
+use std::io::{BufRead, IsTerminal};
+use std::path::PathBuf;
+
+use crate::app::InputMode;
+use crate::gitignore::{LineEnding, WriteMode};
+use crate::recipe::Recipe;
+
+/// Invalid command-line usage; reported with a dedicated exit code.
+#[derive(Debug)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+/// Builds an `anyhow::Error` wrapping a formatted `UsageError`.
+#[macro_export]
+macro_rules! usage_error {
+    ($($arg:tt)*) => {
+        anyhow::Error::new($crate::cli::UsageError(format!($($arg)*)))
+    };
+}
+pub use crate::usage_error;
+
+const HELP: &str = "\
+autogitignore - search, preview, and generate .gitignore files
+
+Usage: autogitignore [OPTIONS] [DIR]
+
+Options:
+  -d, --dir <path>           Directory to write the .gitignore into (default: cwd);
+                             repeat to write the same selection into several directories
+      --create-dir           Create the --dir directory (and parents) if it does not exist
+      --gen <names>          Write the given comma-separated templates without the TUI;
+                             http(s) URLs are fetched and included as their own section
+      --template <name>      Like --gen for a single template; repeatable and combinable with --gen
+      --stdin                Read template names from stdin, one per line (like --gen)
+      --stdout               Print the generated .gitignore instead of writing it
+      --diff                 Print a unified diff of what --gen would change, without writing;
+                             exits with code 4 when any .gitignore would change
+                             (errors exit 1-3 as usual)
+      --export-selection <file>
+                             Save the --gen selection and its custom sections as a shareable
+                             JSON file (`-` for stdout) instead of writing a .gitignore
+      --import-selection <file>
+                             Load a selection saved with --export-selection (`-` for stdin);
+                             preselected in the TUI, or added to --gen when generating
+      --header <header>      Send an extra HTTP header (\"Key: Value\") with template requests;
+                             repeatable
+      --template-dir <path>  Also offer every *.gitignore file in <path> as a template
+  -y, --yes                  Answer prompts automatically (an existing .gitignore is appended to
+                             unless --overwrite is given)
+      --append               Append to an existing .gitignore (preselected in the prompt)
+      --overwrite            Replace an existing .gitignore (preselected in the prompt)
+      --gitattributes        Also write a standard .gitattributes next to the .gitignore
+      --flat                 Write one sorted, deduplicated pattern list instead of per-template sections
+      --no-comments          Strip comment-only lines from template content
+      --keep-whitespace      Keep trailing whitespace on template lines (trimmed by default)
+      --eol <lf|crlf>        Line endings for the written file (default: lf)
+      --start-mode <mode>    Start in `normal` or `search` mode (default: search)
+      --ascii                Draw with plain ASCII symbols (automatic when TERM/locale lack Unicode)
+      --read-only            Browse and preview only; all writes are disabled
+      --allow-outside        Allow writes that resolve outside the output directory
+      --tick-ms <ms>         Input poll / animation interval in milliseconds (default: 100)
+      --refresh              Ignore the local cache and fetch templates again
+      --clear-cache          Delete the local template cache and exit
+      --init                 Fetch templates, write a starter config, and exit
+      --doctor               Check cache, network, and config setup, then exit
+      --print-cache-path     Print the template cache file location and exit
+      --print-config-path    Print the config file location and exit
+      --audit-log            Print the log of files written by autogitignore and exit
+      --stats                Print how often each template was written (with record_stats) and exit
+  -q, --quiet                Don't print what was written (after the TUI exits or with --gen)
+  -h, --help                 Print this help (add --verbose for debugging options)
+";
+
+const VERBOSE_HELP: &str = "
+Debugging options:
+      --dump-raw <file>      Write the raw API response to <file> before parsing
+";
+
+/// Options collected from the command line.
+#[derive(Default)]
+pub struct Args {
+    /// Directories where the .gitignore should be written (repeat `--dir` for several).
+    pub output_dirs: Vec<PathBuf>,
+    /// Template names to generate non-interactively, from `--gen` and `--template` (skips the TUI when non-empty).
+    pub generate: Vec<String>,
+    /// Template names were read from stdin (`--stdin`).
+    pub stdin: bool,
+    /// Print the generated content instead of writing files.
+    pub stdout: bool,
+    /// Print what a write would change instead of writing.
+    pub diff: bool,
+    /// Where `--export-selection` saves the selection (`-` for stdout).
+    pub export_selection: Option<PathBuf>,
+    /// Selection loaded with `--import-selection`.
+    pub imported: Option<Recipe>,
+    /// Extra HTTP headers from `--header`, as (name, value).
+    pub headers: Vec<(String, String)>,
+    /// Directory of local `*.gitignore` templates merged over the remote ones.
+    pub template_dir: Option<PathBuf>,
+    /// Create missing output directories instead of failing.
+    pub create_dir: bool,
+    /// No directory was given on the command line, so `output_dirs` holds only the cwd.
+    pub default_dir: bool,
+    /// How an existing `.gitignore` is handled, from `--append` or `--overwrite`.
+    pub write_mode: Option<WriteMode>,
+    /// Accept every confirmation prompt without asking.
+    pub yes: bool,
+    /// Also write a standard `.gitattributes` next to each `.gitignore`.
+    pub gitattributes: bool,
+    /// Write a single sorted, deduplicated pattern list instead of per-template sections.
+    pub flat: bool,
+    /// Strip upstream comment lines from the generated content.
+    pub no_comments: bool,
+    /// Keep trailing whitespace in template lines instead of trimming it.
+    pub keep_whitespace: bool,
+    /// Line ending style used for the written file.
+    pub eol: LineEnding,
+    /// Disable every write path (browse-only mode).
+    pub read_only: bool,
+    /// Whether writes may resolve outside the output directory (e.g. via symlinks).
+    pub allow_outside: bool,
+    /// Input mode the TUI starts in (search by default).
+    pub start_mode: InputMode,
+    /// Draw the TUI with ASCII-only symbols.
+    pub ascii: bool,
+    /// Input poll / animation tick interval in milliseconds, if overridden.
+    pub tick_ms: Option<u64>,
+    /// Skip the local cache and fetch fresh data on startup.
+    pub refresh: bool,
+    /// Delete the cache and exit.
+    pub clear_cache: bool,
+    /// Run first-time setup (fetch + starter config) and exit.
+    pub init: bool,
+    /// Run setup diagnostics and exit.
+    pub doctor: bool,
+    /// Print the cache file path and exit.
+    pub print_cache_path: bool,
+    /// Print the config file path and exit.
+    pub print_config_path: bool,
+    /// Print the write log and exit.
+    pub audit_log: bool,
+    /// Print the template usage tallies and exit.
+    pub stats: bool,
+    /// File to receive the raw API response body (hidden debugging option).
+    pub dump_raw: Option<PathBuf>,
+    /// Suppress the report of written files.
+    pub quiet: bool,
+    /// Print usage instead of starting the TUI.
+    pub help: bool,
+    /// Include hidden options when printing usage.
+    pub verbose: bool,
+}
+
+/// Prints usage to stdout, including hidden options when `verbose` is set.
+pub fn print_help(verbose: bool) {
+    print!("{}", HELP);
+    if verbose {
+        print!("{}", VERBOSE_HELP);
+    }
+}
+
+/// Parses the process arguments into `Args`, resolving the output directory against the cwd.
+pub fn parse_args() -> Result<Args> {
+    let mut args = std::env::args().skip(1);
+    let mut parsed = Args::default();
+    let mut output_dirs: Vec<PathBuf> = Vec::new();
+    let mut import = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" | "--dir" => {
+                output_dirs.push(PathBuf::from(next_value(&mut args, "--dir", "a path")?));
+            }
+            "--eol" => {
+                let value = next_value(&mut args, "--eol", "a value (lf or crlf)")?;
+                parsed.eol = match value.to_ascii_lowercase().as_str() {
+                    "lf" => LineEnding::Lf,
+                    "crlf" => LineEnding::Crlf,
+                    _ => return Err(usage_error!("Invalid --eol value: {} (expected lf or crlf)", value)),
+                };
+            }
+            "-y" | "--yes" => parsed.yes = true,
+            "--append" | "--overwrite" => {
+                let mode = if arg == "--append" { WriteMode::Append } else { WriteMode::Overwrite };
+                if parsed.write_mode.is_some_and(|current| current != mode) {
+                    return Err(usage_error!("--append and --overwrite cannot be combined"));
+                }
+                parsed.write_mode = Some(mode);
+            }
+            "--create-dir" => parsed.create_dir = true,
+            "--allow-outside" => parsed.allow_outside = true,
+            "--read-only" => parsed.read_only = true,
+            "--no-comments" => parsed.no_comments = true,
+            "--flat" => parsed.flat = true,
+            "--ascii" => parsed.ascii = true,
+            "--keep-whitespace" => parsed.keep_whitespace = true,
+            "--gitattributes" => parsed.gitattributes = true,
+            "--template-dir" => {
+                parsed.template_dir = Some(PathBuf::from(next_value(&mut args, "--template-dir", "a path")?));
+            }
+            "--start-mode" => {
+                let value = next_value(&mut args, "--start-mode", "a value (normal or search)")?;
+                parsed.start_mode = match value.to_ascii_lowercase().as_str() {
+                    "normal" => InputMode::Normal,
+                    "search" => InputMode::Editing,
+                    _ => {
+                        return Err(usage_error!(
+                            "Invalid --start-mode value: {} (expected normal or search)",
+                            value
+                        ));
+                    }
+                };
+            }
+            "--gen" => {
+                let value = next_value(&mut args, "--gen", "a comma-separated list of templates")?;
+                parsed.generate.extend(
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|name| !name.is_empty())
+                        .map(str::to_string),
+                );
+            }
+            "--template" => {
+                let value = next_value(&mut args, "--template", "a template name")?;
+                let name = value.trim();
+                if name.is_empty() {
+                    return Err(usage_error!("--template requires a non-empty template name"));
+                }
+                parsed.generate.push(name.to_string());
+            }
+            "--stdin" => parsed.stdin = true,
+            "--header" => {
+                let value = next_value(&mut args, "--header", "a \"Key: Value\" header")?;
+                let header = crate::api::parse_header(&value)
+                    .map_err(|e| usage_error!("Invalid --header value: {}", e))?;
+                parsed.headers.push(header);
+            }
+            "--export-selection" => {
+                parsed.export_selection =
+                    Some(PathBuf::from(next_value(&mut args, "--export-selection", "a file path")?));
+            }
+            "--import-selection" => {
+                import = Some(PathBuf::from(next_value(&mut args, "--import-selection", "a file path")?));
+            }
+            "--stdout" => parsed.stdout = true,
+            "--diff" => parsed.diff = true,
+            "--tick-ms" => {
+                let value = next_value(&mut args, "--tick-ms", "a number of milliseconds")?;
+                let ms = value
+                    .parse()
+                    .ok()
+                    .filter(|ms| *ms > 0)
+                    .ok_or_else(|| usage_error!("Invalid --tick-ms value: {} (expected a positive number)", value))?;
+                parsed.tick_ms = Some(ms);
+            }
+            "--refresh" => parsed.refresh = true,
+            "--clear-cache" => parsed.clear_cache = true,
+            "--init" => parsed.init = true,
+            "--audit-log" => parsed.audit_log = true,
+            "--stats" => parsed.stats = true,
+            "--print-cache-path" => parsed.print_cache_path = true,
+            "--print-config-path" => parsed.print_config_path = true,
+            "--doctor" => parsed.doctor = true,
+            "--dump-raw" => {
+                parsed.dump_raw = Some(PathBuf::from(next_value(&mut args, "--dump-raw", "a file path")?));
+            }
+            "-q" | "--quiet" => parsed.quiet = true,
+            "-h" | "--help" => parsed.help = true,
+            "-v" | "--verbose" => parsed.verbose = true,
+            _ => {
+                if !output_dirs.is_empty() {
+                    return Err(usage_error!("Unexpected argument: {}", arg));
+                }
+                output_dirs.push(PathBuf::from(arg));
+            }
+        }
+    }
+
+    if parsed.help {
+        return Ok(parsed);
+    }
+    if parsed.stdin && import.as_deref() == Some(std::path::Path::new("-")) {
+        return Err(usage_error!("--stdin and --import-selection - cannot both read stdin"));
+    }
+    if parsed.stdin {
+        parsed.generate.extend(read_stdin_names()?);
+    }
+    if let Some(path) = import {
+        let recipe = Recipe::load(&path)?;
+        // Imported names join a non-interactive run; otherwise the TUI preselects them.
+        if !parsed.generate.is_empty() || parsed.stdout || parsed.diff || parsed.export_selection.is_some() {
+            parsed.generate.extend(recipe.templates.iter().cloned());
+        }
+        parsed.imported = Some(recipe);
+    }
+    if parsed.stdout && parsed.generate.is_empty() {
+        return Err(usage_error!("--stdout requires --gen, --template, --stdin or --import-selection"));
+    }
+    if parsed.diff && parsed.generate.is_empty() {
+        return Err(usage_error!("--diff requires --gen, --template, --stdin or --import-selection"));
+    }
+    if parsed.export_selection.is_some() && parsed.generate.is_empty() {
+        return Err(usage_error!(
+            "--export-selection requires --gen, --template, --stdin or --import-selection"
+        ));
+    }
+    if parsed.diff && parsed.stdout {
+        return Err(usage_error!("--diff cannot be combined with --stdout"));
+    }
+    if parsed.export_selection.is_some() && (parsed.stdout || parsed.diff) {
+        return Err(usage_error!("--export-selection cannot be combined with --stdout or --diff"));
+    }
+    if parsed.read_only
+        && !parsed.generate.is_empty()
+        && !parsed.stdout
+        && !parsed.diff
+        && parsed.export_selection.is_none()
+    {
+        return Err(usage_error!("--gen/--template cannot be combined with --read-only"));
+    }
+
+    if parsed.create_dir && parsed.read_only {
+        return Err(usage_error!("--create-dir cannot be combined with --read-only"));
+    }
+
+    if output_dirs.is_empty() {
+        parsed.default_dir = true;
+        parsed.output_dirs.push(resolve_output_dir(None, false)?);
+    }
+    for dir in output_dirs {
+        parsed.output_dirs.push(resolve_output_dir(Some(dir), parsed.create_dir)?);
+    }
+    Ok(parsed)
+}
+
+/// Reads template names from piped stdin, one per line, skipping blank lines.
+/// Refuses an interactive terminal rather than waiting for input that never comes.
+fn read_stdin_names() -> Result<Vec<String>> {
+    let stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        return Err(usage_error!("--stdin expects template names piped on stdin"));
+    }
+    let mut names = Vec::new();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let name = line.trim();
+        if !name.is_empty() {
+            names.push(name.to_string());
+        }
+    }
+    if names.is_empty() {
+        return Err(usage_error!("--stdin received no template names"));
+    }
+    Ok(names)
+}
+
+/// Takes the value following `flag`, failing with a usage error if it is missing.
+fn next_value(args: &mut impl Iterator<Item = String>, flag: &str, expected: &str) -> Result<String> {
+    args.next()
+        .ok_or_else(|| usage_error!("{} requires {}", flag, expected))
+}
+
+/// Resolves the output directory against the cwd, creating it when missing and `create` is set.
+/// A missing path and a path to a file get distinct errors; the latter suggests the file's directory.
+fn resolve_output_dir(output_dir: Option<PathBuf>, create: bool) -> Result<PathBuf> {
+    let cwd = std::env::current_dir()?;
+    let dir = output_dir.map_or(cwd.clone(), |path| {
+        if path.is_absolute() {
+            path
+        } else {
+            cwd.join(path)
+        }
+    });
+
+    if dir.is_dir() {
+        return Ok(dir);
+    }
+    if dir.exists() {
+        let hint = dir
+            .parent()
+            .map(|parent| format!(" (did you mean its directory, {}?)", parent.display()))
+            .unwrap_or_default();
+        return Err(usage_error!("Target path is a file, not a directory: {}{}", dir.display(), hint));
+    }
+    if !create {
+        return Err(usage_error!(
+            "Target directory does not exist: {} (pass --create-dir to create it)",
+            dir.display()
+        ));
+    }
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    Ok(dir)
+}
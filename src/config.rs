@@ -0,0 +1,240 @@
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::gitignore::{DEFAULT_SECTION_HEADER, SECTION_NAME_PLACEHOLDER};
+
+/// Resolves the OS-specific project directories used for cache and config files.
+pub fn project_dirs() -> Result<ProjectDirs> {
+    ProjectDirs::from("com", "autogitignore", "autogitignore")
+        .ok_or_else(|| anyhow::anyhow!("Failed to determine cache directory"))
+}
+
+/// Default soft cap on selected templates before a warning is shown.
+const DEFAULT_SELECTION_WARNING: usize = 25;
+
+/// Default number of removed lines past which Overwrite asks for a second confirmation.
+const DEFAULT_OVERWRITE_WARNING: usize = 20;
+
+/// Order in which template sections are written to the generated file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SectionOrder {
+    /// Sections sorted by template name.
+    #[default]
+    Alphabetical,
+    /// Languages first, then editors, then operating systems; alphabetical within each group.
+    Category,
+    /// The order templates were selected in (adjustable with Shift+Up/Down).
+    Selection,
+}
+
+/// Order of the template list while the search box is empty.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListOrder {
+    /// Sorted by name.
+    #[default]
+    Alphabetical,
+    /// Most often written first, from the local usage stats.
+    MostUsed,
+}
+
+/// How the search query is matched against templates.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// Fuzzy matching, ranked by score.
+    #[default]
+    Fuzzy,
+    /// Case-insensitive name prefix matching, listed alphabetically.
+    Prefix,
+    /// Case-insensitive substring matching.
+    Substring,
+    /// Case-insensitive regular expression matching.
+    Regex,
+    /// Case-insensitive substring matching against names and template contents.
+    Content,
+}
+
+impl SearchMode {
+    /// The mode after this one in the cycle.
+    pub fn next(self) -> Self {
+        match self {
+            SearchMode::Fuzzy => SearchMode::Prefix,
+            SearchMode::Prefix => SearchMode::Substring,
+            SearchMode::Substring => SearchMode::Regex,
+            SearchMode::Regex => SearchMode::Content,
+            SearchMode::Content => SearchMode::Fuzzy,
+        }
+    }
+
+    /// Short name shown in the search pane title.
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchMode::Fuzzy => "Fuzzy",
+            SearchMode::Prefix => "Prefix",
+            SearchMode::Substring => "Substring",
+            SearchMode::Regex => "Regex",
+            SearchMode::Content => "Content",
+        }
+    }
+}
+
+/// A named search query that can be applied with a number key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedFilter {
+    /// Name shown in the search pane while the filter is active.
+    pub name: String,
+    /// Search query applied when the filter is activated.
+    pub query: String,
+    /// Search mode to switch to; the current mode is kept when unset.
+    #[serde(default)]
+    pub mode: Option<SearchMode>,
+}
+
+/// Persistent user preferences, stored as JSON in the OS config directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Templates hidden from the list unless blocked templates are shown.
+    pub blocked_templates: Vec<String>,
+    /// Switch to the Combined preview automatically once more than one template is selected.
+    pub auto_combined_preview: bool,
+    /// Header line written before each template section; `{name}` is the template name.
+    /// An empty string writes no headers.
+    pub section_header: String,
+    /// How sections are ordered in the generated file.
+    pub section_order: SectionOrder,
+    /// Order of the unfiltered template list.
+    pub list_order: ListOrder,
+    /// Count how often each template is written, in a local stats file (never sent anywhere).
+    pub record_stats: bool,
+    /// Search mode the TUI starts in.
+    pub search_mode: SearchMode,
+    /// Denser template list: bullet markers and, on wide panes, two columns.
+    pub compact_list: bool,
+    /// Quit when Esc is pressed in Normal mode (otherwise only `q` quits).
+    pub esc_quits: bool,
+    /// Quit after Enter saves successfully (otherwise Enter saves and stays open, like Ctrl+S).
+    pub enter_quits: bool,
+    /// Maximum number of template contents kept in the cache file (unlimited when unset).
+    pub max_cached_templates: Option<usize>,
+    /// Selection size past which a one-time warning is shown (`null` or 0 disables it).
+    pub selection_warning: Option<usize>,
+    /// Saved search queries, activated with the number keys 1-9 in order.
+    pub saved_filters: Vec<SavedFilter>,
+    /// Free-text sections by name, offered alongside the templates and marked `(custom)`.
+    pub custom_sections: BTreeMap<String, String>,
+    /// Default to the last directory written to when no `--dir` is given (instead of the cwd).
+    pub remember_output_dir: bool,
+    /// Directory of the last successful write, recorded while `remember_output_dir` is on.
+    pub last_output_dir: Option<PathBuf>,
+    /// Never replace an existing file: only Append is offered and `--overwrite` is refused.
+    pub never_overwrite: bool,
+    /// Lines an Overwrite may remove before it needs a second confirmation (`null` or 0 disables it).
+    pub overwrite_warning: Option<usize>,
+    /// Extra HTTP headers sent with every template request (e.g. `Authorization` for a mirror).
+    pub headers: BTreeMap<String, String>,
+    /// Key overrides by action name (e.g. `"save": ["ctrl+w"]`), replacing that action's default keys.
+    pub keybindings: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            blocked_templates: Vec::new(),
+            auto_combined_preview: false,
+            section_header: DEFAULT_SECTION_HEADER.to_string(),
+            section_order: SectionOrder::default(),
+            list_order: ListOrder::default(),
+            record_stats: false,
+            search_mode: SearchMode::default(),
+            compact_list: false,
+            esc_quits: false,
+            enter_quits: true,
+            max_cached_templates: None,
+            selection_warning: Some(DEFAULT_SELECTION_WARNING),
+            never_overwrite: false,
+            overwrite_warning: Some(DEFAULT_OVERWRITE_WARNING),
+            saved_filters: Vec::new(),
+            custom_sections: BTreeMap::new(),
+            remember_output_dir: false,
+            last_output_dir: None,
+            headers: BTreeMap::new(),
+            keybindings: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Location of the config file.
+    pub fn path() -> Result<PathBuf> {
+        Ok(project_dirs()?.config_dir().join("config.json"))
+    }
+
+    /// Loads the config file, falling back to defaults when it doesn't exist yet.
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)?;
+        let config: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid config file: {}", path.display()))?;
+        config
+            .validate()
+            .with_context(|| format!("Invalid config file: {}", path.display()))?;
+        Ok(config)
+    }
+
+    /// Rejects settings that would produce unusable output.
+    fn validate(&self) -> Result<()> {
+        let header = &self.section_header;
+        if !header.is_empty() && !header.contains(SECTION_NAME_PLACEHOLDER) {
+            return Err(anyhow::anyhow!(
+                "section_header must contain {} (got {:?}); use \"\" to disable headers",
+                SECTION_NAME_PLACEHOLDER,
+                header
+            ));
+        }
+        if header.contains('\n') {
+            return Err(anyhow::anyhow!("section_header must be a single line"));
+        }
+        for (name, value) in &self.headers {
+            crate::api::parse_header(&format!("{}: {}", name, value)).context("Invalid entry in headers")?;
+        }
+        crate::keymap::Keymap::resolve(&self.keybindings).context("Invalid keybindings")?;
+        Ok(())
+    }
+
+    /// Writes the config file, creating its directory if needed.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_header(header: &str) -> Config {
+        Config { section_header: header.to_string(), ..Config::default() }
+    }
+
+    #[test]
+    fn section_header_must_name_the_template() {
+        assert!(with_header("### {name} ###").validate().is_ok());
+        assert!(with_header("").validate().is_ok());
+        assert!(with_header("### section ###").validate().is_err());
+        assert!(with_header("# {name}\n#").validate().is_err());
+    }
+}
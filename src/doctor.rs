@@ -0,0 +1,88 @@
+use anyhow::Result;
+use std::fs;
+
+use crate::api::ApiClient;
+use crate::config::Config;
+
+/// Result of a single diagnostic check.
+enum Check {
+    Pass(String),
+    Skip(String),
+    Fail(String),
+}
+
+/// Runs the setup diagnostics (`--doctor`), printing one line per check.
+/// Fails if any check fails, so the exit code reflects the overall result.
+pub async fn run(client: &ApiClient) -> Result<()> {
+    let checks = [
+        ("Cache directory writable", check_cache_dir(client)),
+        ("Template source reachable", check_network(client).await),
+        ("Cache file valid", check_cache_file(client)),
+        ("Config file valid", check_config()),
+    ];
+
+    let mut failed = 0;
+    for (name, check) in &checks {
+        let (label, detail) = match check {
+            Check::Pass(detail) => ("PASS", detail),
+            Check::Skip(detail) => ("SKIP", detail),
+            Check::Fail(detail) => {
+                failed += 1;
+                ("FAIL", detail)
+            }
+        };
+        println!("[{}] {}: {}", label, name, detail);
+    }
+
+    if failed > 0 {
+        return Err(anyhow::anyhow!("{} of {} checks failed", failed, checks.len()));
+    }
+    println!("All checks passed.");
+    Ok(())
+}
+
+fn check_cache_dir(client: &ApiClient) -> Check {
+    let Some(dir) = client.cache_path().parent() else {
+        return Check::Fail("cache path has no parent directory".to_string());
+    };
+    let probe = dir.join(".doctor-probe");
+    match fs::write(&probe, b"ok").and_then(|()| fs::remove_file(&probe)) {
+        Ok(()) => Check::Pass(dir.display().to_string()),
+        Err(e) => Check::Fail(format!("{}: {}", dir.display(), e)),
+    }
+}
+
+async fn check_network(client: &ApiClient) -> Check {
+    match client.check_reachable().await {
+        Ok(()) => Check::Pass("gitignore.io responded".to_string()),
+        Err(e) => Check::Fail(format!("{:#}", e)),
+    }
+}
+
+fn check_cache_file(client: &ApiClient) -> Check {
+    let path = client.cache_path();
+    if !path.exists() {
+        return Check::Skip(format!("no cache yet at {} (run --init)", path.display()));
+    }
+    match client.load_cache() {
+        Some(cache) => Check::Pass(format!("{} templates in {}", cache.templates.len(), path.display())),
+        None => Check::Fail(format!(
+            "{} is corrupted or unreadable; it will be re-fetched (or run --clear-cache)",
+            path.display()
+        )),
+    }
+}
+
+fn check_config() -> Check {
+    let path = match Config::path() {
+        Ok(path) => path,
+        Err(e) => return Check::Fail(format!("{:#}", e)),
+    };
+    if !path.exists() {
+        return Check::Skip(format!("no config at {} (defaults are used)", path.display()));
+    }
+    match Config::load() {
+        Ok(_) => Check::Pass(path.display().to_string()),
+        Err(e) => Check::Fail(format!("{:#}", e)),
+    }
+}
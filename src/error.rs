@@ -0,0 +1,64 @@
+use std::fmt;
+
+/// An error shown in the status pane, categorized so the UI can label it and offer a retry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    /// Talking to the template source failed or timed out.
+    Network(String),
+    /// A response or cache file couldn't be understood.
+    Parse(String),
+    /// Writing an output file failed.
+    Write(String),
+    /// Loading or saving the config failed.
+    Config(String),
+    /// The terminal or an external program misbehaved.
+    System(String),
+    /// The requested action isn't possible right now (e.g. nothing selected).
+    Input(String),
+}
+
+impl AppError {
+    /// Categorizes a failed template fetch by looking at its error chain.
+    pub fn from_fetch(err: &anyhow::Error) -> Self {
+        let message = format!("{:#}", err);
+        if err.chain().any(|cause| cause.is::<serde_json::Error>()) {
+            AppError::Parse(message)
+        } else {
+            AppError::Network(message)
+        }
+    }
+
+    /// Short badge text for the status pane.
+    pub fn label(&self) -> &'static str {
+        match self {
+            AppError::Network(_) => "NETWORK",
+            AppError::Parse(_) => "PARSE",
+            AppError::Write(_) => "WRITE",
+            AppError::Config(_) => "CONFIG",
+            AppError::System(_) => "SYSTEM",
+            AppError::Input(_) => "ERROR",
+        }
+    }
+
+    /// Whether re-running the template sync (`R`) may fix this error.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, AppError::Network(_) | AppError::Parse(_))
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            AppError::Network(message)
+            | AppError::Parse(message)
+            | AppError::Write(message)
+            | AppError::Config(message)
+            | AppError::System(message)
+            | AppError::Input(message) => message,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
@@ -1,9 +1,12 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 
+use crate::lock;
+
 /// Defines how the new content should be written to the .gitignore file.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum WriteMode {
     /// Append to the end of the existing file (with a backup).
     Append,
@@ -11,39 +14,603 @@ pub enum WriteMode {
     Overwrite,
 }
 
-/// Writes the selected template content to a .gitignore file in the target directory.
-/// Always creates a .gitignore.bak if an existing file is modified or overwritten.
-pub fn write_gitignore(path: &Path, content: &str, mode: WriteMode) -> Result<()> {
-    let backup_path = path.with_file_name(".gitignore.bak");
+/// What a write actually did, beyond what was requested. The default is a plain, locked write.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WriteOutcome {
+    /// The existing file wasn't valid UTF-8, so it was overwritten (after a backup) instead of appended to.
+    pub overwrote_unreadable: bool,
+    /// No lock file could be created, so nothing stopped another instance writing at the same time.
+    pub unlocked: bool,
+}
 
-    match mode {
-        WriteMode::Append if path.exists() => {
-            fs::copy(path, backup_path)?;
+impl WriteOutcome {
+    /// Message explaining what was unusual about writing `path`, if anything.
+    pub fn notice(self, path: &Path) -> Option<String> {
+        let mut notes = Vec::new();
+        if self.overwrote_unreadable {
+            notes.push(format!(
+                "{} was not valid UTF-8, so it was overwritten instead of appended to (backup kept as .bak)",
+                path.display()
+            ));
+        }
+        if self.unlocked {
+            notes.push(format!(
+                "{} was written without a lock (the lock directory is unavailable)",
+                path.display()
+            ));
+        }
+        (!notes.is_empty()).then(|| notes.join("; "))
+    }
+}
 
-            let existing = fs::read_to_string(path)?;
-            let mut new_content = existing;
+/// Line ending style applied to the written .gitignore file.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum LineEnding {
+    /// Unix-style `\n` line endings.
+    #[default]
+    Lf,
+    /// Windows-style `\r\n` line endings.
+    Crlf,
+}
 
-            if !new_content.ends_with('\n') && !new_content.is_empty() {
-                new_content.push('\n');
+impl LineEnding {
+    /// Normalizes every line break in `text` (LF, CRLF or mixed) to this style.
+    pub fn apply(self, text: &str) -> String {
+        let normalized = text.replace("\r\n", "\n");
+        match self {
+            LineEnding::Lf => normalized,
+            LineEnding::Crlf => normalized.replace('\n', "\r\n"),
+        }
+    }
+}
+
+/// Removes trailing whitespace from every line. A space escaped with a trailing backslash
+/// (`foo\ `) is significant in gitignore patterns and is kept.
+pub fn trim_trailing_whitespace(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_end();
+            if trimmed.ends_with('\\') && trimmed.len() < line.len() {
+                &line[..trimmed.len() + 1]
+            } else {
+                trimmed
             }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-            new_content.push_str("\n# --- Added by autogitignore ---\n");
-            new_content.push_str(content);
-            new_content.push('\n');
+/// Slice of `content` without blank (whitespace-only) lines at the start and end.
+pub fn trim_blank_lines(content: &str) -> &str {
+    let mut start = 0;
+    for line in content.split_inclusive('\n') {
+        if !line.trim().is_empty() {
+            break;
+        }
+        start += line.len();
+    }
+    let rest = &content[start..];
+    let mut end = rest.len();
+    while let Some(newline) = rest[..end].rfind('\n') {
+        if !rest[newline + 1..end].trim().is_empty() {
+            break;
+        }
+        end = newline;
+    }
+    if rest[..end].trim().is_empty() {
+        return "";
+    }
+    &rest[..end]
+}
 
-            fs::write(path, new_content)?;
+/// Removes comment-only lines from template content, collapsing the blank lines left behind.
+pub fn strip_comments(content: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in content.lines() {
+        if line.trim_start().starts_with('#') {
+            continue;
         }
-        _ => {
-            // Overwrite OR path doesn't exist
-            if path.exists() {
-                fs::copy(path, backup_path)?;
-            }
-            let mut new_content = String::from("# .gitignore generated by autogitignore\n\n");
-            new_content.push_str(content);
-            new_content.push('\n');
-            fs::write(path, new_content)?;
+        let blank = line.trim().is_empty();
+        if blank && lines.last().is_none_or(|prev| prev.trim().is_empty()) {
+            continue;
         }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
     }
+    lines.join("\n")
+}
+
+/// Merges template contents into one sorted, deduplicated list of patterns (comments and
+/// blank lines dropped). Negations (`!pattern`) go last so they still override the patterns
+/// they re-include, whatever template they came from.
+pub fn flatten<'a>(contents: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let mut patterns: Vec<&str> = contents
+        .into_iter()
+        .flat_map(str::lines)
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .collect();
+    patterns.sort_by_key(|line| (line.starts_with('!'), *line));
+    patterns.dedup();
+    patterns
+}
+
+/// Standard `.gitattributes` rules written alongside the .gitignore with `--gitattributes`.
+/// Kept under a `DEFAULT_SECTION_HEADER` section so repeated appends replace it in place.
+pub const GITATTRIBUTES_CONTENT: &str = "\
+# --- Defaults ---
+# Normalize line endings for text files
+* text=auto
+
+# Common binary files
+*.png binary
+*.jpg binary
+*.jpeg binary
+*.gif binary
+*.ico binary
+*.pdf binary
+*.zip binary
+*.gz binary
+";
+
+/// Marker line placed before sections appended to an existing file.
+const APPEND_MARKER: &str = "# --- Added by autogitignore ---";
 
+/// Default section header; `{name}` is replaced with the template name.
+pub const DEFAULT_SECTION_HEADER: &str = "# --- {name} ---";
+
+/// Placeholder substituted with the template name in a section header template.
+pub const SECTION_NAME_PLACEHOLDER: &str = "{name}";
+
+/// Renders the section header for `name`. An empty template means no header line.
+pub fn section_header(template: &str, name: &str) -> String {
+    template.replace(SECTION_NAME_PLACEHOLDER, name)
+}
+
+/// Extracts `name` from a line rendered from the section header `template`.
+fn section_name<'a>(line: &'a str, template: &str) -> Option<&'a str> {
+    let (prefix, suffix) = template.split_once(SECTION_NAME_PLACEHOLDER)?;
+    let name = line.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    (!name.is_empty()).then_some(name)
+}
+
+/// Splits generated text into the lines before the first section header and `(name, lines)` blocks,
+/// where each block runs from its header up to the next header.
+fn split_sections<'a>(text: &'a str, template: &str) -> (Vec<&'a str>, Vec<(&'a str, Vec<&'a str>)>) {
+    let mut preamble = Vec::new();
+    let mut sections: Vec<(&str, Vec<&str>)> = Vec::new();
+    for line in text.lines() {
+        match section_name(line, template) {
+            Some(name) => sections.push((name, vec![line])),
+            None => match sections.last_mut() {
+                Some((_, lines)) => lines.push(line),
+                None => preamble.push(line),
+            },
+        }
+    }
+    (preamble, sections)
+}
+
+/// End (exclusive) of the existing section whose header is at `lines[start]`. The section covers
+/// the lines that belong to its template `body`, and stops at the first line that doesn't (user
+/// content), at the append marker or at the next header. Blank lines only count when more of the
+/// template follows them, so the spacing before whatever comes next is kept.
+fn section_end(lines: &[&str], start: usize, body: &HashSet<&str>, template: &str) -> usize {
+    let mut end = start + 1;
+    for (i, line) in lines.iter().enumerate().skip(start + 1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if *line == APPEND_MARKER || section_name(line, template).is_some() || !body.contains(line.trim_end()) {
+            break;
+        }
+        end = i + 1;
+    }
+    end
+}
+
+/// Computes the result of appending generated `content` to `existing` (both LF-normalized).
+/// Sections already present (matched by their header, rendered from `header_template`) are replaced
+/// in place, so repeated runs stay idempotent; only new sections are appended after the marker.
+/// Lines around a replaced section that aren't part of its template are left untouched.
+pub fn merge_append(existing: &str, content: &str, header_template: &str) -> String {
+    let (_, mut new_sections) = split_sections(content, header_template);
+    for (_, lines) in &mut new_sections {
+        while lines.last().is_some_and(|l| l.trim().is_empty()) {
+            lines.pop();
+        }
+    }
+
+    let lines: Vec<&str> = existing.lines().collect();
+    let mut result: Vec<&str> = Vec::with_capacity(lines.len());
+    let mut i = 0;
+    while i < lines.len() {
+        let replacement = section_name(lines[i], header_template)
+            .and_then(|name| new_sections.iter().position(|(n, _)| *n == name));
+        let Some(index) = replacement else {
+            result.push(lines[i]);
+            i += 1;
+            continue;
+        };
+        let (_, new_lines) = new_sections.remove(index);
+        let body: HashSet<&str> = new_lines[1..].iter().map(|l| l.trim_end()).collect();
+        i = section_end(&lines, i, &body, header_template);
+        result.extend(new_lines);
+    }
+
+    let mut new_content = result.join("\n");
+    if !new_content.is_empty() {
+        new_content.push('\n');
+    }
+
+    if !new_sections.is_empty() {
+        new_content.push('\n');
+        new_content.push_str(APPEND_MARKER);
+        new_content.push('\n');
+        new_content.push_str(
+            &new_sections
+                .into_iter()
+                .map(|(_, lines)| lines.join("\n"))
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        );
+        new_content.push('\n');
+    }
+    new_content
+}
+
+/// Number of non-blank lines in `existing` that don't appear anywhere in `new`,
+/// i.e. what an overwrite would throw away.
+pub fn removed_lines(existing: &str, new: &str) -> usize {
+    let kept: HashSet<&str> = new.lines().map(str::trim_end).collect();
+    existing
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty() && !kept.contains(line))
+        .count()
+}
+
+/// Ensures `target` (following symlinks) resolves to a location inside `dir`.
+/// Used to refuse writes that would escape the chosen output directory.
+pub fn ensure_within(dir: &Path, target: &Path) -> Result<()> {
+    let root = dir.canonicalize()?;
+    let resolved = if fs::symlink_metadata(target).is_ok() {
+        target.canonicalize().map_err(|e| {
+            anyhow::anyhow!("Cannot resolve write target {}: {}", target.display(), e)
+        })?
+    } else {
+        let parent = target
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Write target has no parent: {}", target.display()))?;
+        let file_name = target
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Write target has no file name: {}", target.display()))?;
+        parent.canonicalize()?.join(file_name)
+    };
+
+    if !resolved.starts_with(&root) {
+        return Err(anyhow::anyhow!(
+            "Refusing to write outside {}: target resolves to {} (use --allow-outside to override)",
+            root.display(),
+            resolved.display()
+        ));
+    }
     Ok(())
 }
+
+/// What `path` would contain after writing `content` in `mode`, and what that write would do.
+/// Appends merge into the existing (LF-normalized) text; everything else is a fresh file with a header.
+/// An existing file that isn't valid UTF-8 can't be merged, so Append falls back to Overwrite.
+pub fn planned_content(
+    path: &Path,
+    content: &str,
+    mode: WriteMode,
+    eol: LineEnding,
+    header_template: &str,
+) -> Result<(String, WriteOutcome)> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Write target has no file name: {}", path.display()))?
+        .to_string_lossy();
+    let content = LineEnding::Lf.apply(content);
+
+    if let WriteMode::Append = mode
+        && path.exists()
+    {
+        let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let Ok(existing) = String::from_utf8(bytes) else {
+            let outcome = WriteOutcome { overwrote_unreadable: true, ..WriteOutcome::default() };
+            return Ok((eol.apply(&fresh_content(&file_name, &content)), outcome));
+        };
+        let merged = merge_append(&LineEnding::Lf.apply(&existing), &content, header_template);
+        return Ok((eol.apply(&merged), WriteOutcome::default()));
+    }
+    Ok((eol.apply(&fresh_content(&file_name, &content)), WriteOutcome::default()))
+}
+
+/// Writes the selected template content to a .gitignore (or companion) file in the target directory.
+/// Always creates a `<name>.bak` if an existing file is modified or overwritten.
+/// The final file is normalized to the requested line ending, including any existing content.
+/// `header_template` identifies existing sections when appending (see `planned_content`).
+pub fn write_gitignore(
+    path: &Path,
+    content: &str,
+    mode: WriteMode,
+    eol: LineEnding,
+    header_template: &str,
+) -> Result<WriteOutcome> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Write target has no file name: {}", path.display()))?;
+    let mut backup_name = file_name.to_os_string();
+    backup_name.push(".bak");
+    let backup_path = path.with_file_name(backup_name);
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty())
+        && !parent.is_dir()
+    {
+        return Err(anyhow::anyhow!("Target directory does not exist: {}", parent.display()));
+    }
+
+    // Held until the write finishes, so a concurrent instance fails instead of clobbering it.
+    let lock = lock::acquire(path)?;
+    let (new_content, mut outcome) = planned_content(path, content, mode, eol, header_template)?;
+    outcome.unlocked = lock.is_none();
+    if path.exists() {
+        fs::copy(path, &backup_path).with_context(|| format!("Failed to back up {}", path.display()))?;
+    }
+    fs::write(path, new_content).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(outcome)
+}
+
+/// Unified diff from `old` to `new` for `path`, or `None` when they are identical.
+/// A missing file is diffed against `/dev/null`, as git does.
+pub fn unified_diff(path: &Path, old: Option<&str>, new: &str) -> Option<String> {
+    let name = path.display().to_string();
+    let old_name = if old.is_some() { name.as_str() } else { "/dev/null" };
+    labeled_diff(old_name, old.unwrap_or(""), &name, new)
+}
+
+/// Unified line diff from `old` (labeled `old_name`) to `new`, or `None` when they are identical.
+pub fn labeled_diff(old_name: &str, old: &str, new_name: &str, new: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+    let diff = similar::TextDiff::from_lines(old, new);
+    Some(diff.unified_diff().context_radius(3).header(old_name, new_name).to_string())
+}
+
+/// A new file named `file_name`: the generated-file header followed by `content` (LF line endings).
+pub fn fresh_content(file_name: &str, content: &str) -> String {
+    let mut new_content = format!("# {} generated by autogitignore\n\n", file_name);
+    new_content.push_str(content);
+    new_content.push('\n');
+    new_content
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = "# --- Rust ---\ntarget/";
+
+    fn write(path: &Path, mode: WriteMode) -> Result<WriteOutcome> {
+        write_gitignore(path, BODY, mode, LineEnding::Lf, DEFAULT_SECTION_HEADER)
+    }
+
+    #[test]
+    fn overwrite_replaces_existing_content_exactly() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".gitignore");
+        fs::write(&path, "old\n").unwrap();
+
+        assert!(!write(&path, WriteMode::Overwrite).unwrap().overwrote_unreadable);
+        assert_eq!(fs::read_to_string(&path).unwrap(), fresh_content(".gitignore", BODY));
+        assert_eq!(fs::read_to_string(dir.path().join(".gitignore.bak")).unwrap(), "old\n");
+    }
+
+    #[test]
+    fn append_adds_sections_after_marker_and_keeps_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".gitignore");
+        fs::write(&path, "node_modules/\n").unwrap();
+
+        write(&path, WriteMode::Append).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("node_modules/\n\n{}\n{}\n", APPEND_MARKER, BODY)
+        );
+        assert_eq!(fs::read_to_string(dir.path().join(".gitignore.bak")).unwrap(), "node_modules/\n");
+    }
+
+    #[test]
+    fn append_to_missing_file_writes_fresh_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".gitignore");
+
+        let (planned, outcome) =
+            planned_content(&path, BODY, WriteMode::Append, LineEnding::Lf, DEFAULT_SECTION_HEADER).unwrap();
+        assert_eq!(planned, fresh_content(".gitignore", BODY));
+        assert_eq!(outcome, WriteOutcome::default());
+        write(&path, WriteMode::Append).unwrap();
+        assert!(!dir.path().join(".gitignore.bak").exists());
+    }
+
+    #[test]
+    fn merge_append_is_idempotent() {
+        let content = "\n# --- Rust ---\ntarget/\n\n# --- Node ---\nnode_modules/\n";
+        let once = merge_append("dist/\n", content, DEFAULT_SECTION_HEADER);
+        assert_eq!(merge_append(&once, content, DEFAULT_SECTION_HEADER), once);
+        assert_eq!(once.matches("# --- Rust ---").count(), 1);
+    }
+
+    #[test]
+    fn merge_append_keeps_user_lines_after_a_replaced_section() {
+        let existing = "# --- Rust ---\ntarget/\n*.old\n\n# my own rules\nsecrets.env\n";
+        let merged = merge_append(existing, "\n# --- Rust ---\ntarget/\nCargo.lock\n", DEFAULT_SECTION_HEADER);
+        assert_eq!(merged, "# --- Rust ---\ntarget/\nCargo.lock\n*.old\n\n# my own rules\nsecrets.env\n");
+    }
+
+    #[test]
+    fn merge_append_keeps_the_marker_and_what_follows_it() {
+        let existing = format!("# --- Rust ---\ntarget/\n\n{}\n# --- Node ---\nnode_modules/\n", APPEND_MARKER);
+        let merged = merge_append(&existing, "\n# --- Rust ---\ntarget/\n**/*.rs.bk\n", DEFAULT_SECTION_HEADER);
+        assert_eq!(
+            merged,
+            format!("# --- Rust ---\ntarget/\n**/*.rs.bk\n\n{}\n# --- Node ---\nnode_modules/\n", APPEND_MARKER)
+        );
+    }
+
+    #[test]
+    fn merge_append_replaces_sections_with_inner_blank_lines() {
+        let content = "\n# --- Defaults ---\n* text=auto\n\n*.png binary\n";
+        let existing = "# --- Defaults ---\n* text=auto\n\n*.png binary\n\nlocal.txt\n";
+        assert_eq!(merge_append(existing, content, DEFAULT_SECTION_HEADER), existing);
+    }
+
+    #[test]
+    fn missing_target_directory_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing").join(".gitignore");
+
+        let err = write(&path, WriteMode::Overwrite).unwrap_err();
+        assert!(err.to_string().contains("Target directory does not exist"), "{err}");
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn unwritable_target_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        // A directory in place of the file can't be written, even with elevated permissions.
+        let path = dir.path().join(".gitignore");
+        fs::create_dir(&path).unwrap();
+
+        let err = write(&path, WriteMode::Overwrite).unwrap_err();
+        assert!(format!("{err:#}").contains(".gitignore"), "{err:#}");
+    }
+
+    #[test]
+    fn strip_comments_drops_comment_lines_and_collapses_blanks() {
+        let content = "# Build output\ntarget/\n\n  # indented comment\n\n\\#literal\n*.log # not a comment\n\n# trailing\n";
+        assert_eq!(strip_comments(content), "target/\n\n\\#literal\n*.log # not a comment");
+    }
+
+    #[test]
+    fn custom_section_headers_render_and_replace_in_place() {
+        let template = "### {name} ###";
+        assert_eq!(section_header(template, "Rust"), "### Rust ###");
+        assert_eq!(section_name("### Rust ###", template), Some("Rust"));
+        assert_eq!(section_name("# --- Rust ---", template), None);
+
+        let existing = "### Rust ###\ntarget/\n";
+        let merged = merge_append(existing, "\n### Rust ###\ntarget/\nCargo.lock\n", template);
+        assert_eq!(merged, "### Rust ###\ntarget/\nCargo.lock\n");
+    }
+
+    #[test]
+    fn trim_trailing_whitespace_keeps_escaped_spaces() {
+        let content = "target/   \nname\\ \ntwo\\   \nplain\\\n\t\n";
+        assert_eq!(trim_trailing_whitespace(content), "target/\nname\\ \ntwo\\ \nplain\\\n");
+    }
+
+    #[test]
+    fn append_to_non_utf8_file_overwrites_with_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".gitignore");
+        let original = b"build/\n\xff\xfe\n";
+        fs::write(&path, original).unwrap();
+
+        assert!(write(&path, WriteMode::Append).unwrap().overwrote_unreadable);
+        assert_eq!(fs::read_to_string(&path).unwrap(), fresh_content(".gitignore", BODY));
+        assert_eq!(fs::read(dir.path().join(".gitignore.bak")).unwrap(), original);
+        let unreadable = WriteOutcome { overwrote_unreadable: true, ..WriteOutcome::default() };
+        assert!(unreadable.notice(&path).unwrap().contains("not valid UTF-8"));
+        assert!(WriteOutcome::default().notice(&path).is_none());
+    }
+
+    #[test]
+    fn trim_blank_lines_keeps_inner_spacing() {
+        assert_eq!(trim_blank_lines("\n  \n# Logs\n*.log\n\n*.tmp\n\n \t\n"), "# Logs\n*.log\n\n*.tmp");
+        assert_eq!(trim_blank_lines(" \n\n"), "");
+        assert_eq!(trim_blank_lines("  indented"), "  indented");
+    }
+
+    #[test]
+    fn flatten_sorts_dedups_and_puts_negations_last() {
+        let go = "# Binaries\n*.exe\n!keep.exe\nbin/\n";
+        let node = "node_modules/\n\n*.exe\n!bin/tool\n";
+        assert_eq!(flatten([go, node]), ["*.exe", "bin/", "node_modules/", "!bin/tool", "!keep.exe"]);
+    }
+
+    #[test]
+    fn line_endings_normalize_mixed_input() {
+        let mixed = "a\r\nb\nc\r\n";
+        assert_eq!(LineEnding::Lf.apply(mixed), "a\nb\nc\n");
+        assert_eq!(LineEnding::Crlf.apply(mixed), "a\r\nb\r\nc\r\n");
+        assert_eq!(LineEnding::Crlf.apply(&LineEnding::Crlf.apply(mixed)), "a\r\nb\r\nc\r\n");
+    }
+
+    #[test]
+    fn crlf_applies_to_merged_existing_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".gitignore");
+        fs::write(&path, "dist/\n").unwrap();
+
+        write_gitignore(&path, BODY, WriteMode::Append, LineEnding::Crlf, DEFAULT_SECTION_HEADER).unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("dist/\r\n"), "{written:?}");
+        assert!(!written.replace("\r\n", "").contains('\n'), "{written:?}");
+    }
+
+    #[test]
+    fn ensure_within_accepts_targets_inside_the_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(ensure_within(dir.path(), &dir.path().join(".gitignore")).is_ok());
+        let nested = dir.path().join("sub");
+        fs::create_dir(&nested).unwrap();
+        assert!(ensure_within(dir.path(), &nested.join("..").join(".gitignore")).is_ok());
+        assert!(ensure_within(&nested, &nested.join("..").join(".gitignore")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn ensure_within_rejects_a_symlink_escaping_the_directory() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let target = outside.path().join("victim");
+        fs::write(&target, "keep me\n").unwrap();
+        let link = root.path().join(".gitignore");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let err = ensure_within(root.path(), &link).unwrap_err();
+        assert!(err.to_string().contains("Refusing to write outside"), "{err}");
+
+        // A dangling link can't be resolved, so it is refused too.
+        fs::remove_file(&target).unwrap();
+        assert!(ensure_within(root.path(), &link).is_err());
+    }
+
+    #[test]
+    fn unified_diff_shows_changes_against_the_existing_file() {
+        let path = Path::new("repo/.gitignore");
+        assert_eq!(unified_diff(path, Some("target/\n"), "target/\n"), None);
+
+        let diff = unified_diff(path, Some("target/\n"), "target/\nCargo.lock\n").unwrap();
+        assert_eq!(diff, "--- repo/.gitignore\n+++ repo/.gitignore\n@@ -1 +1,2 @@\n target/\n+Cargo.lock\n");
+
+        let created = unified_diff(path, None, "target/\n").unwrap();
+        assert!(created.starts_with("--- /dev/null\n+++ repo/.gitignore\n"), "{created}");
+    }
+
+    #[test]
+    fn unlocked_writes_are_reported() {
+        let path = Path::new(".gitignore");
+        let outcome = WriteOutcome { overwrote_unreadable: true, unlocked: true };
+        let notice = outcome.notice(path).unwrap();
+        assert!(notice.contains("not valid UTF-8") && notice.contains("written without a lock"), "{notice}");
+    }
+}
+
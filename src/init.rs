@@ -0,0 +1,31 @@
+use anyhow::Result;
+
+use crate::api::{self, ApiClient};
+use crate::config::Config;
+
+/// First-time setup: warms the template cache and writes a starter config if none exists.
+/// Safe to run again; the cache is refreshed and an existing config is left untouched.
+pub async fn run(client: &ApiClient) -> Result<()> {
+    println!("Fetching templates from gitignore.io...");
+    let cache = client.fetch_all_data().await?;
+    if let Some(notice) = api::oversized_notice(&cache) {
+        eprintln!("Warning: {}", notice);
+    }
+    client.save_cache(&cache)?;
+    println!(
+        "Cached {} templates at {}",
+        cache.templates.len(),
+        client.cache_path().display()
+    );
+
+    let config_path = Config::path()?;
+    if config_path.exists() {
+        println!("Config already exists at {}", config_path.display());
+    } else {
+        Config::default().save()?;
+        println!("Wrote starter config to {}", config_path.display());
+    }
+
+    println!("Setup complete. Run `autogitignore` to start.");
+    Ok(())
+}
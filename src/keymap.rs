@@ -0,0 +1,344 @@
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+
+/// Something a key does while browsing the template list (Normal mode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    ToggleSelect,
+    SelectAndPreview,
+    Search,
+    Down,
+    Up,
+    MoveSelectionDown,
+    MoveSelectionUp,
+    ScrollPreviewDown,
+    ScrollPreviewUp,
+    PageDown,
+    PageUp,
+    ScrollRight,
+    ScrollLeft,
+    TogglePreviewMode,
+    Peek,
+    Zoom,
+    Wrap,
+    Syntax,
+    Pin,
+    Compare,
+    RefreshPreview,
+    Jump,
+    CycleSearchMode,
+    PrefixSearch,
+    DeselectFiltered,
+    Block,
+    ShowBlocked,
+    CycleSource,
+    CompactList,
+    StripComments,
+    CustomSection,
+    OpenDir,
+    Retry,
+    ClearCache,
+    Save,
+    SaveAndQuit,
+    QuickPick,
+    Help,
+    Quit,
+}
+
+/// Every action with its config name, help text and default keys, in the order help lists them.
+const ACTIONS: &[(Action, &str, &str, &[&str])] = &[
+    (Action::ToggleSelect, "select", "Select / deselect the highlighted template", &["space"]),
+    (Action::SelectAndPreview, "select_and_preview", "Select and show the Combined preview", &["v"]),
+    (Action::Search, "search", "Search templates", &["/", "i"]),
+    (Action::Down, "down", "Move down", &["down", "j"]),
+    (Action::Up, "up", "Move up", &["up", "k"]),
+    (Action::MoveSelectionDown, "move_selection_down", "Move a selected template later (selection order)", &["shift+down"]),
+    (Action::MoveSelectionUp, "move_selection_up", "Move a selected template earlier (selection order)", &["shift+up"]),
+    (Action::ScrollPreviewDown, "scroll_preview_down", "Scroll the preview down", &["alt+down", "alt+j"]),
+    (Action::ScrollPreviewUp, "scroll_preview_up", "Scroll the preview up", &["alt+up", "alt+k"]),
+    (Action::PageDown, "page_down", "Scroll the preview a page down", &["pagedown"]),
+    (Action::PageUp, "page_up", "Scroll the preview a page up", &["pageup"]),
+    (Action::ScrollRight, "scroll_right", "Scroll the preview right (wrapping off)", &["right"]),
+    (Action::ScrollLeft, "scroll_left", "Scroll the preview left (wrapping off)", &["left"]),
+    (Action::TogglePreviewMode, "preview_mode", "Switch between Highlighted and Combined preview", &["p"]),
+    (Action::Peek, "peek", "Show the highlighted template in the Combined preview", &["P"]),
+    (Action::Zoom, "zoom", "Zoom the preview to the whole screen", &["z"]),
+    (Action::Wrap, "wrap", "Toggle preview line wrapping", &["w"]),
+    (Action::Syntax, "syntax", "Toggle preview syntax colors", &["h"]),
+    (Action::Pin, "pin", "Pin the highlighted template's preview", &["f"]),
+    (Action::Compare, "compare", "Mark templates to compare", &["C"]),
+    (Action::RefreshPreview, "refresh_preview", "Rebuild the preview", &["ctrl+l"]),
+    (Action::Jump, "jump", "Jump to a template by first letter", &["'"]),
+    (Action::CycleSearchMode, "search_mode", "Cycle the search mode", &["ctrl+f"]),
+    (Action::PrefixSearch, "prefix_search", "Toggle prefix search", &["ctrl+p"]),
+    (Action::DeselectFiltered, "deselect_filtered", "Deselect every listed template", &["d"]),
+    (Action::Block, "block", "Block / unblock the highlighted template", &["b"]),
+    (Action::ShowBlocked, "show_blocked", "Show / hide blocked templates", &["B"]),
+    (Action::CycleSource, "source_filter", "Cycle which template sources are listed", &["S"]),
+    (Action::CompactList, "compact_list", "Toggle the compact list", &["m"]),
+    (Action::StripComments, "strip_comments", "Toggle stripping comments from the output", &["c"]),
+    (Action::CustomSection, "custom_section", "Add a custom section", &["a"]),
+    (Action::OpenDir, "open_dir", "Open the output directory", &["o"]),
+    (Action::Retry, "retry", "Retry failed downloads, or reload the templates", &["R"]),
+    (Action::ClearCache, "clear_cache", "Clear the cache and reload", &["X"]),
+    (Action::Save, "save", "Save and stay open", &["ctrl+s"]),
+    (Action::SaveAndQuit, "save_and_quit", "Save (and quit, with enter_quits)", &["enter"]),
+    (Action::QuickPick, "quick_pick", "Select the top match and save", &["tab"]),
+    (Action::Help, "help", "Show all keys", &["?"]),
+    (Action::Quit, "quit", "Quit", &["q"]),
+];
+
+/// Keys handled outside the keymap, listed after the actions in help.
+pub const FIXED_KEYS: &[(&str, &str)] = &[
+    ("1-9", "Apply a saved filter"),
+    ("ESC", "Clear a comparison (or quit, with esc_quits)"),
+];
+
+/// One key with its modifiers, e.g. `ctrl+s` or `P`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    /// Parses `[ctrl+][alt+][shift+]key`, where key is a single character (case-sensitive) or a
+    /// named key such as `space`, `enter`, `tab`, `esc`, `up` or `pagedown`.
+    fn parse(text: &str) -> Result<Self> {
+        let (prefix, key) = match text.rsplit_once('+') {
+            Some((prefix, "")) => (prefix.strip_suffix('+').unwrap_or(prefix), "+"),
+            Some((prefix, key)) => (prefix, key),
+            None => ("", text),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(anyhow::anyhow!("unknown modifier {:?} in key {:?}", modifier, text)),
+            };
+        }
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "esc" => KeyCode::Esc,
+                "backspace" => KeyCode::Backspace,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                _ => return Err(anyhow::anyhow!("unknown key {:?}", text)),
+            },
+        };
+        Ok(Self { code, modifiers })
+    }
+
+    /// Whether `key` is this binding. Shift is ignored for characters, whose case already shows it.
+    fn matches(&self, key: &KeyEvent) -> bool {
+        let ignored = if matches!(key.code, KeyCode::Char(_)) { KeyModifiers::SHIFT } else { KeyModifiers::NONE };
+        let wanted = self.modifiers - ignored;
+        match (self.code, key.code) {
+            (KeyCode::Char(a), KeyCode::Char(b)) if !wanted.is_empty() => {
+                a.eq_ignore_ascii_case(&b) && key.modifiers - ignored == wanted
+            }
+            (a, b) => a == b && key.modifiers - ignored == wanted,
+        }
+    }
+
+    /// Label shown in the shortcut bar and help, e.g. `CTRL+S`, `SPACE` or `P`.
+    fn label(&self) -> String {
+        let mut label = String::new();
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "CTRL+"),
+            (KeyModifiers::ALT, "ALT+"),
+            (KeyModifiers::SHIFT, "SHIFT+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                label.push_str(name);
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => label.push_str("SPACE"),
+            // Case matters for plain characters (`p` and `P` differ), not with Ctrl or Alt.
+            KeyCode::Char(c) if self.modifiers.is_empty() => label.push(c),
+            KeyCode::Char(c) => label.extend(c.to_uppercase()),
+            code => label.push_str(match code {
+                KeyCode::Enter => "ENTER",
+                KeyCode::Tab => "TAB",
+                KeyCode::Esc => "ESC",
+                KeyCode::Backspace => "BACKSPACE",
+                KeyCode::Up => "UP",
+                KeyCode::Down => "DOWN",
+                KeyCode::Left => "LEFT",
+                KeyCode::Right => "RIGHT",
+                KeyCode::PageUp => "PGUP",
+                KeyCode::PageDown => "PGDN",
+                KeyCode::Home => "HOME",
+                KeyCode::End => "END",
+                _ => "?",
+            }),
+        }
+        label
+    }
+}
+
+/// Normal-mode key bindings: the defaults with the config's `keybindings` applied.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeyBinding>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = ACTIONS
+            .iter()
+            .map(|(action, _, _, keys)| {
+                let keys = keys.iter().map(|key| KeyBinding::parse(key).expect("valid default key")).collect();
+                (*action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Applies `overrides` (action name -> keys, replacing that action's defaults) to the defaults.
+    /// A key claimed by an override is taken away from any other action's defaults; an empty
+    /// list unbinds the action. Unknown actions or keys, and one key for two overrides, are errors.
+    pub fn resolve(overrides: &BTreeMap<String, Vec<String>>) -> Result<Self> {
+        let mut keymap = Self::default();
+        let mut claimed: Vec<(KeyBinding, &str)> = Vec::new();
+        for (name, keys) in overrides {
+            let action = ACTIONS
+                .iter()
+                .find(|(_, config_name, _, _)| config_name == name)
+                .map(|(action, _, _, _)| *action)
+                .ok_or_else(|| anyhow::anyhow!("unknown action {:?} in keybindings", name))?;
+            let mut parsed = Vec::new();
+            for key in keys {
+                let binding = KeyBinding::parse(key)?;
+                if let Some((_, other)) = claimed.iter().find(|(b, _)| *b == binding) {
+                    return Err(anyhow::anyhow!("key {:?} is bound to both {} and {}", key, other, name));
+                }
+                claimed.push((binding, name));
+                parsed.push(binding);
+            }
+            keymap.set(action, parsed);
+        }
+        for (action, keys) in &mut keymap.bindings {
+            if !overrides.contains_key(config_name(*action)) {
+                keys.retain(|key| !claimed.iter().any(|(claimed, _)| claimed == key));
+            }
+        }
+        Ok(keymap)
+    }
+
+    fn set(&mut self, action: Action, keys: Vec<KeyBinding>) {
+        if let Some((_, bound)) = self.bindings.iter_mut().find(|(a, _)| *a == action) {
+            *bound = keys;
+        }
+    }
+
+    /// The action bound to `key`, if any.
+    pub fn action_for(&self, key: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.iter().any(|binding| binding.matches(key)))
+            .map(|(action, _)| *action)
+    }
+
+    /// Keys bound to `action` as shown to the user (e.g. `/, i`), or `None` when it is unbound.
+    pub fn label(&self, action: Action) -> Option<String> {
+        let (_, keys) = self.bindings.iter().find(|(a, _)| *a == action)?;
+        if keys.is_empty() {
+            return None;
+        }
+        Some(keys.iter().map(KeyBinding::label).collect::<Vec<_>>().join(", "))
+    }
+
+    /// Every bound action as `(keys, description)`, in help order.
+    pub fn help_lines(&self) -> Vec<(String, &'static str)> {
+        ACTIONS
+            .iter()
+            .filter_map(|(action, _, description, _)| Some((self.label(*action)?, *description)))
+            .collect()
+    }
+}
+
+/// Name of `action` in the config's `keybindings`.
+fn config_name(action: Action) -> &'static str {
+    ACTIONS
+        .iter()
+        .find(|(a, _, _, _)| *a == action)
+        .map_or("", |(_, name, _, _)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn overrides(entries: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(name, keys)| (name.to_string(), keys.iter().map(|k| k.to_string()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn defaults_keep_the_built_in_keys() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.action_for(&key(KeyCode::Char('p'), KeyModifiers::NONE)), Some(Action::TogglePreviewMode));
+        assert_eq!(keymap.action_for(&key(KeyCode::Char('P'), KeyModifiers::SHIFT)), Some(Action::Peek));
+        assert_eq!(keymap.action_for(&key(KeyCode::Char('p'), KeyModifiers::CONTROL)), Some(Action::PrefixSearch));
+        assert_eq!(keymap.action_for(&key(KeyCode::Char('j'), KeyModifiers::ALT)), Some(Action::ScrollPreviewDown));
+        assert_eq!(keymap.action_for(&key(KeyCode::Down, KeyModifiers::SHIFT)), Some(Action::MoveSelectionDown));
+        assert_eq!(keymap.action_for(&key(KeyCode::Down, KeyModifiers::NONE)), Some(Action::Down));
+        assert_eq!(keymap.action_for(&key(KeyCode::Char('1'), KeyModifiers::NONE)), None);
+    }
+
+    #[test]
+    fn override_takes_the_key_from_its_default_action() {
+        let keymap = Keymap::resolve(&overrides(&[("pin", &["p"])])).unwrap();
+        assert_eq!(keymap.action_for(&key(KeyCode::Char('p'), KeyModifiers::NONE)), Some(Action::Pin));
+        assert_eq!(keymap.action_for(&key(KeyCode::Char('f'), KeyModifiers::NONE)), None);
+        assert_eq!(keymap.label(Action::TogglePreviewMode), None);
+        assert!(!keymap.help_lines().iter().any(|(_, desc)| desc.contains("Highlighted and Combined")));
+    }
+
+    #[test]
+    fn invalid_overrides_are_errors() {
+        assert!(Keymap::resolve(&overrides(&[("teleport", &["t"])])).is_err());
+        assert!(Keymap::resolve(&overrides(&[("save", &["hyper+s"])])).is_err());
+        assert!(Keymap::resolve(&overrides(&[("save", &["ctrl+w"]), ("quit", &["ctrl+w"])])).is_err());
+    }
+
+    #[test]
+    fn labels_follow_the_bindings() {
+        let keymap = Keymap::resolve(&overrides(&[("save", &["ctrl+w"]), ("wrap", &["shift+right"])])).unwrap();
+        assert_eq!(keymap.label(Action::Save).as_deref(), Some("CTRL+W"));
+        assert_eq!(keymap.label(Action::Wrap).as_deref(), Some("SHIFT+RIGHT"));
+        assert_eq!(keymap.label(Action::Search).as_deref(), Some("/, i"));
+        assert_eq!(keymap.label(Action::ToggleSelect).as_deref(), Some("SPACE"));
+    }
+
+    #[test]
+    fn modified_characters_match_either_case() {
+        let keymap = Keymap::resolve(&overrides(&[("jump", &["ctrl+g"])])).unwrap();
+        assert_eq!(keymap.action_for(&key(KeyCode::Char('G'), KeyModifiers::CONTROL)), Some(Action::Jump));
+        assert_eq!(keymap.action_for(&key(KeyCode::Char('g'), KeyModifiers::NONE)), None);
+    }
+}
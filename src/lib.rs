@@ -0,0 +1,25 @@
+//! Template search, generation and writing behind the autogitignore binary, exposed as a
+//! library so benchmarks can exercise it.
+
+pub mod aliases;
+pub mod api;
+pub mod app;
+pub mod audit;
+pub mod batch;
+pub mod categories;
+pub mod cli;
+pub mod config;
+pub mod doctor;
+pub mod error;
+pub mod gitignore;
+pub mod init;
+pub mod keymap;
+pub mod local;
+pub mod lock;
+pub mod models;
+pub mod opener;
+pub mod project;
+pub mod recipe;
+pub mod stats;
+pub mod symbols;
+pub mod ui;
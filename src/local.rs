@@ -0,0 +1,26 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Reads every `*.gitignore` file in `dir` as a template, keyed by file name without the extension.
+pub fn load_template_dir(dir: &Path) -> Result<HashMap<String, String>> {
+    let mut templates = HashMap::new();
+    let entries = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read template directory: {}", dir.display()))?;
+
+    for entry in entries {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().is_none_or(|ext| ext != "gitignore") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read template: {}", path.display()))?;
+        templates.insert(name.to_string(), content);
+    }
+
+    Ok(templates)
+}
@@ -0,0 +1,87 @@
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+
+use crate::config::project_dirs;
+
+/// Advisory lock on a write target, held until dropped.
+/// The lock file lives in the OS data directory, so nothing is added next to the target.
+pub struct WriteLock {
+    _file: File,
+}
+
+/// Locks `target` against writes from other autogitignore instances.
+/// Fails if another process holds the lock or locking itself errors. Returns `None` when no lock
+/// file can be created (e.g. without a data directory); the caller then writes unlocked and says so.
+pub fn acquire(target: &Path) -> Result<Option<WriteLock>> {
+    let locks_dir = project_dirs().ok().map(|dirs| dirs.data_dir().join("locks"));
+    acquire_in(locks_dir.as_deref(), target)
+}
+
+/// `acquire`, keeping lock files in `locks_dir`.
+fn acquire_in(locks_dir: Option<&Path>, target: &Path) -> Result<Option<WriteLock>> {
+    let Some(lock_path) = locks_dir.and_then(|dir| lock_path(dir, target)) else {
+        return Ok(None);
+    };
+    if lock_path.parent().is_none_or(|dir| fs::create_dir_all(dir).is_err()) {
+        return Ok(None);
+    }
+    let Ok(file) = OpenOptions::new().create(true).truncate(false).write(true).open(&lock_path) else {
+        return Ok(None);
+    };
+    match file.try_lock() {
+        Ok(()) => Ok(Some(WriteLock { _file: file })),
+        Err(TryLockError::WouldBlock) => Err(anyhow::anyhow!(
+            "{} is locked by another process (another autogitignore is writing it); try again",
+            target.display()
+        )),
+        Err(TryLockError::Error(e)) => {
+            Err(e).with_context(|| format!("Failed to lock {} ({})", target.display(), lock_path.display()))
+        }
+    }
+}
+
+/// Lock file in `locks_dir` for `target`, named after a hash of its absolute path (resolving
+/// symlinked directories).
+fn lock_path(locks_dir: &Path, target: &Path) -> Option<PathBuf> {
+    let dir = target.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let resolved = fs::canonicalize(dir).ok()?.join(target.file_name()?);
+    let name = format!("{:x}.lock", Sha256::digest(resolved.to_string_lossy().as_bytes()));
+    Some(locks_dir.join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_held_lock_blocks_others_until_dropped() {
+        let locks = tempfile::tempdir().unwrap();
+        let repo = tempfile::tempdir().unwrap();
+        let target = repo.path().join(".gitignore");
+
+        let first = acquire_in(Some(locks.path()), &target).unwrap();
+        assert!(first.is_some());
+        let err = acquire_in(Some(locks.path()), &target).err().unwrap();
+        assert!(err.to_string().contains("locked by another process"), "{err}");
+
+        // Another target isn't affected.
+        assert!(acquire_in(Some(locks.path()), &repo.path().join(".gitattributes")).unwrap().is_some());
+
+        drop(first);
+        assert!(acquire_in(Some(locks.path()), &target).unwrap().is_some());
+    }
+
+    #[test]
+    fn no_lock_directory_means_an_unlocked_write() {
+        let repo = tempfile::tempdir().unwrap();
+        let target = repo.path().join(".gitignore");
+        assert!(acquire_in(None, &target).unwrap().is_none());
+
+        // A file where the lock directory should be can't hold lock files either.
+        let blocker = repo.path().join("locks");
+        fs::write(&blocker, "").unwrap();
+        assert!(acquire_in(Some(&blocker.join("nested")), &target).unwrap().is_none());
+    }
+}
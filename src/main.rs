@@ -1,41 +1,89 @@
-mod api;
-mod app;
-mod gitignore;
-mod models;
-mod ui;
-
-use crate::models::CacheData;
-use crate::ui::draw;
-use anyhow::Result;
+use autogitignore::models::CacheData;
+use autogitignore::ui::draw;
+use autogitignore::{api, app, audit, batch, cli, config, doctor, error, gitignore, init, keymap, local, opener, project, stats};
+use anyhow::{Context, Result};
 use app::{App, InputMode};
+use keymap::Action;
+use error::AppError;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::{io, path::PathBuf, time::Duration};
+use std::{
+    collections::HashMap,
+    io::{self, IsTerminal},
+    process::ExitCode,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
 use tokio::sync::mpsc;
 
+/// Default input poll / animation tick interval in milliseconds.
+const DEFAULT_TICK_MS: u64 = 100;
+
+/// Upper bound for a full template sync before it is reported as hung.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
 enum AppEvent {
+    /// Animation frame; only sent while something is animating.
     Tick,
+    /// The terminal was resized, so the whole UI must be redrawn.
+    Resize,
     Key(event::KeyEvent),
     DataLoaded(CacheData),
-    Error(String),
+    /// Fresh data from a background refresh started while cached data was shown.
+    Refreshed(CacheData),
+    /// The background refresh failed; the cached data stays in place.
+    RefreshFailed,
+    ContentsLoaded(HashMap<String, String>, Vec<(String, String)>),
+    /// Writing the cache file failed; the reason is shown as a warning.
+    CacheSaveFailed(String),
+    Error(AppError),
 }
 
 struct TerminalSession {
     terminal: Terminal<CrosstermBackend<io::Stdout>>,
+    /// Whether the alternate screen was entered (and must be left on drop).
+    alternate_screen: bool,
+    /// Explanation shown in the UI when the session had to fall back to inline rendering.
+    warning: Option<String>,
 }
 
 impl TerminalSession {
+    /// Sets up the terminal. If the alternate screen is unsupported, renders inline instead
+    /// (with a warning); any other failure restores the terminal before returning the error.
     fn new() -> Result<Self> {
-        enable_raw_mode()?;
+        enable_raw_mode().context("This terminal does not support raw mode, which the TUI requires")?;
+        Self::setup().inspect_err(|_| {
+            let _ = disable_raw_mode();
+        })
+    }
+
+    fn setup() -> Result<Self> {
         let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+        let (alternate_screen, warning) = match execute!(stdout, EnterAlternateScreen) {
+            Ok(()) => (true, None),
+            Err(e) => (
+                false,
+                Some(format!("Alternate screen unavailable ({}); rendering inline.", e)),
+            ),
+        };
+        let _ = execute!(stdout, EnableMouseCapture);
         let backend = CrosstermBackend::new(stdout);
-        let terminal = Terminal::new(backend)?;
-        Ok(Self { terminal })
+        let mut terminal = Terminal::new(backend).context("Failed to initialize the terminal")?;
+        if !alternate_screen {
+            let _ = terminal.clear();
+        }
+        Ok(Self {
+            terminal,
+            alternate_screen,
+            warning,
+        })
     }
 
     fn terminal_mut(&mut self) -> &mut Terminal<CrosstermBackend<io::Stdout>> {
@@ -46,81 +94,250 @@ impl TerminalSession {
 impl Drop for TerminalSession {
     fn drop(&mut self) {
         let _ = disable_raw_mode();
-        let _ = execute!(
-            self.terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        );
+        if self.alternate_screen {
+            let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
+        } else {
+            let _ = self.terminal.clear();
+        }
+        let _ = execute!(self.terminal.backend_mut(), DisableMouseCapture);
         let _ = self.terminal.show_cursor();
     }
 }
 
+/// Process exit codes: 0 success, 1 generic error, 2 usage error, 3 network error,
+/// 4 `--diff` found changes (and nothing failed).
+const EXIT_FAILURE: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_NETWORK: u8 = 3;
+const EXIT_CHANGES: u8 = 4;
+
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
+    // `run` owns the terminal session, so it is torn down before the error is printed.
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) if e.is::<batch::ChangesPending>() => {
+            eprintln!("{}", e);
+            ExitCode::from(EXIT_CHANGES)
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            ExitCode::from(exit_code(&e))
+        }
+    }
+}
+
+/// Maps an error to its exit code category.
+fn exit_code(err: &anyhow::Error) -> u8 {
+    if err.downcast_ref::<cli::UsageError>().is_some() {
+        EXIT_USAGE
+    } else if err.chain().any(|cause| cause.is::<reqwest::Error>()) {
+        EXIT_NETWORK
+    } else {
+        EXIT_FAILURE
+    }
+}
+
+async fn run() -> Result<()> {
+    let mut args = cli::parse_args()?;
+    if args.help {
+        cli::print_help(args.verbose);
+        return Ok(());
+    }
+    if args.print_cache_path {
+        println!("{}", api::cache_file_path()?.display());
+        return Ok(());
+    }
+    if args.print_config_path {
+        println!("{}", config::Config::path()?.display());
+        return Ok(());
+    }
+    if args.audit_log {
+        return audit::print();
+    }
+    if args.stats {
+        return stats::print();
+    }
+    // Config headers first so `--header` wins; a broken config is left for `--doctor` and the load below.
+    let mut headers: Vec<(String, String)> = config::Config::load()
+        .map(|config| config.headers.into_iter().collect())
+        .unwrap_or_default();
+    headers.extend(args.headers.iter().cloned());
+    let client = api::ApiClient::new(&headers)?.with_raw_dump(args.dump_raw.clone());
+    if args.clear_cache {
+        client.clear_cache()?;
+        println!("Cleared cache at {}", client.cache_path().display());
+        return Ok(());
+    }
+
+    if args.init {
+        return init::run(&client).await;
+    }
+
+    if args.doctor {
+        return doctor::run(&client).await;
+    }
+
+    let config = config::Config::load()?;
+    if config.never_overwrite && args.write_mode == Some(gitignore::WriteMode::Overwrite) {
+        return Err(cli::usage_error!("--overwrite is not allowed while never_overwrite is set in the config"));
+    }
+    let remembered_dir = config
+        .last_output_dir
+        .clone()
+        .filter(|dir| args.default_dir && config.remember_output_dir && dir.is_dir());
+    if let Some(dir) = &remembered_dir {
+        args.output_dirs = vec![dir.clone()];
+    }
+    let client = Arc::new(client.with_content_limit(config.max_cached_templates));
+    if !args.generate.is_empty() {
+        return batch::run(&args, config, &client).await;
+    }
+
+    // Raw mode and the alternate screen need a terminal on both ends; point scripts at the batch flags.
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Err(cli::usage_error!(
+            "the interactive UI needs a terminal; for scripts use --gen <names> (or --stdin), \
+             optionally with --stdout or --diff"
+        ));
+    }
     let mut session = TerminalSession::new()?;
-    let output_dir = parse_output_dir()?;
-    let mut app = App::new(output_dir);
+    let mut app = App::new(&args, config);
+    app.remembered_output_dir = remembered_dir.is_some();
+    app.error = session.warning.take().map(AppError::System);
+    app.pending_defaults = match &args.imported {
+        Some(recipe) => Some(("imported selection", recipe.templates.clone())),
+        None => project::load_defaults(&app.output_dirs[0]).map(|names| ("project defaults", names)),
+    };
+    if app.config.list_order == config::ListOrder::MostUsed {
+        app.usage_counts = stats::load().unwrap_or_default();
+    }
+    if let Some(dir) = &args.template_dir {
+        app.local_template_contents = local::load_template_dir(dir)?;
+    }
     let (tx, mut rx) = mpsc::channel(100);
 
-    // Sync / Cache logic
-    let client = crate::api::ApiClient::new()?;
-    let tx_c = tx.clone();
-
-    // Check cache
-    if let Some(cache) = client.load_cache() {
-        let _ = tx_c.send(AppEvent::DataLoaded(cache)).await;
+    // Stale-while-revalidate: show the cache immediately and refresh it in the background.
+    if let Some(cache) = client.load_cache().filter(|_| !args.refresh) {
+        let _ = tx.send(AppEvent::DataLoaded(cache)).await;
+        app.refreshing = true;
+        spawn_refresh(client.clone(), tx.clone());
     } else {
         // FULL SYNC from Toptal
-        tokio::spawn(async move {
-            match client.fetch_all_data().await {
-                Ok(cache) => {
-                    let _ = client.save_cache(&cache);
-                    let _ = tx_c.send(AppEvent::DataLoaded(cache)).await;
-                }
-                Err(e) => {
-                    let _ = tx_c.send(AppEvent::Error(e.to_string())).await;
-                }
-            }
-        });
+        spawn_fetch(client.clone(), tx.clone());
     }
 
-    // Event loop thread
+    // Event loop thread. Ticks are only sent while something is animating (e.g. loading),
+    // so an idle app sleeps on `rx.recv()` instead of waking up every interval.
     let tx_c = tx.clone();
+    let animating = Arc::new(AtomicBool::new(app.is_loading));
+    let animating_c = animating.clone();
+    let tick_interval = Duration::from_millis(args.tick_ms.unwrap_or(DEFAULT_TICK_MS));
     tokio::spawn(async move {
         loop {
-            if event::poll(Duration::from_millis(100)).unwrap_or(false) {
+            if event::poll(tick_interval).unwrap_or(false) {
                 match event::read() {
                     Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
                         let _ = tx_c.send(AppEvent::Key(key)).await;
                     }
+                    Ok(Event::Resize(_, _)) => {
+                        let _ = tx_c.send(AppEvent::Resize).await;
+                    }
                     Ok(_) => {}
                     Err(err) => {
-                        let _ = tx_c.send(AppEvent::Error(err.to_string())).await;
+                        let _ = tx_c.send(AppEvent::Error(AppError::System(err.to_string()))).await;
                     }
                 }
+            } else if animating_c.load(Ordering::Relaxed) {
+                let _ = tx_c.send(AppEvent::Tick).await;
             }
-            let _ = tx_c.send(AppEvent::Tick).await;
         }
     });
 
+    // Set by every event that can change what is shown; a stray tick after loading ends leaves it clear.
+    let mut dirty = true;
     'main_loop: loop {
-        session.terminal_mut().draw(|f| draw(f, &mut app))?;
+        animating.store(app.is_loading, Ordering::Relaxed);
+        if dirty {
+            session.terminal_mut().draw(|f| draw(f, &mut app))?;
+        }
 
         if let Some(ev) = rx.recv().await {
+            dirty = !matches!(ev, AppEvent::Tick) || app.is_loading;
             match ev {
-                AppEvent::Tick => {}
+                AppEvent::Tick => app.spinner_frame = app.spinner_frame.wrapping_add(1),
+                AppEvent::Resize => {}
                 AppEvent::Error(e) => {
                     app.error = Some(e);
                     app.is_loading = false;
                 }
                 AppEvent::DataLoaded(cache) => {
-                    app.set_templates(cache.templates);
-                    app.template_contents = cache.contents;
+                    if let Some(changes) = app.describe_template_changes(&cache.templates) {
+                        app.notification = Some(changes);
+                    }
+                    if let Some(notice) = api::oversized_notice(&cache) {
+                        app.notification = Some(notice);
+                    }
+                    app.mark_new_templates(&cache.templates);
+                    app.load_data(cache);
                     app.is_loading = false;
                     app.apply_filter();
+                    app.apply_project_defaults();
+                }
+                AppEvent::Refreshed(cache) => {
+                    app.refreshing = false;
+                    // Skip when the user started a reload meanwhile; its result supersedes this one.
+                    if !app.is_loading {
+                        let changed = app
+                            .template_change_counts(&cache.templates)
+                            .is_some_and(|(added, removed)| added + removed > 0);
+                        if changed {
+                            app.notification = app.describe_template_changes(&cache.templates);
+                        }
+                        if let Some(notice) = api::oversized_notice(&cache) {
+                            app.notification = Some(notice);
+                        }
+                        app.mark_new_templates(&cache.templates);
+                        app.load_data(cache);
+                        app.apply_filter();
+                    }
+                }
+                AppEvent::RefreshFailed => {
+                    app.refreshing = false;
+                    app.refresh_failed = true;
+                }
+                AppEvent::ContentsLoaded(contents, failures) => {
+                    app.apply_fetched_contents(contents, failures);
+                }
+                AppEvent::CacheSaveFailed(reason) => {
+                    app.notification = Some(format!(
+                        "Warning: couldn't save the template cache{}data will be fetched again next time ({})",
+                        app.symbols.dash, reason
+                    ));
                 }
                 AppEvent::Key(key) => match app.input_mode {
+                    // With nothing loaded there is nothing to search, so the retry key retries instead.
+                    InputMode::Editing
+                        if app.error.as_ref().is_some_and(AppError::is_retryable)
+                            && app.templates.is_empty()
+                            && app.keymap.action_for(&key) == Some(Action::Retry) =>
+                    {
+                        retry_fetch(&mut app, &client, &tx);
+                    }
                     InputMode::Editing => match key.code {
+                        // Only modified keys act while typing; plain characters go into the query.
+                        KeyCode::Char(_)
+                            if key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                                && app.keymap.action_for(&key) == Some(Action::CycleSearchMode) =>
+                        {
+                            app.cycle_search_mode();
+                        }
+                        KeyCode::Char(_)
+                            if key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                                && app.keymap.action_for(&key) == Some(Action::PrefixSearch) =>
+                        {
+                            app.toggle_prefix_search();
+                        }
                         KeyCode::Char(c) => {
                             app.notification = None;
                             app.error = None;
@@ -136,131 +353,228 @@ async fn main() -> Result<()> {
                         KeyCode::Esc | KeyCode::Enter => {
                             app.input_mode = InputMode::Normal;
                         }
+                        KeyCode::Tab => quick_pick(&mut app, &client),
                         KeyCode::Down => app.next(),
                         KeyCode::Up => app.previous(),
                         _ => {}
                     },
                     InputMode::Normal => match key.code {
-                        KeyCode::Char('i') | KeyCode::Char('/') => {
-                            app.notification = None;
-                            app.error = None;
-                            app.input_mode = InputMode::Editing;
-                        }
-                        KeyCode::Char('q') | KeyCode::Esc => {
+                        KeyCode::Esc if app.comparison.is_some() => app.clear_compare(),
+                        // With quit rebound away, ESC is the only way out.
+                        KeyCode::Esc if app.config.esc_quits || app.keymap.label(Action::Quit).is_none() => {
                             break;
                         }
-                        KeyCode::Down | KeyCode::Char('j')
-                            if key.modifiers.contains(KeyModifiers::ALT) =>
-                        {
-                            let max_scroll = app.max_preview_scroll();
-                            if app.preview_scroll < max_scroll {
-                                app.preview_scroll = app.preview_scroll.saturating_add(1);
-                            }
-                        }
-                        KeyCode::Up | KeyCode::Char('k')
-                            if key.modifiers.contains(KeyModifiers::ALT) =>
-                        {
-                            app.preview_scroll = app.preview_scroll.saturating_sub(1);
+                        KeyCode::Esc => {
+                            app.error = None;
+                            app.notification =
+                                app.keymap.label(Action::Quit).map(|keys| format!("Press {} to quit.", keys));
                         }
-                        KeyCode::Down | KeyCode::Char('j') => app.next(),
-                        KeyCode::Up | KeyCode::Char('k') => app.previous(),
-                        KeyCode::Char(' ') => app.toggle_selection(),
-                        KeyCode::Char('p') => {
-                            app.preview_mode = match app.preview_mode {
-                                crate::app::PreviewMode::Highlighted => {
-                                    crate::app::PreviewMode::Combined
+                        _ => match app.keymap.action_for(&key) {
+                            Some(Action::Search) => {
+                                app.notification = None;
+                                app.error = None;
+                                app.input_mode = InputMode::Editing;
+                            }
+                            Some(Action::Quit) => {
+                                break;
+                            }
+                            Some(Action::Help) => app.input_mode = InputMode::Help,
+                            Some(Action::ScrollPreviewDown) => {
+                                let max_scroll = app.max_preview_scroll();
+                                if app.preview_scroll < max_scroll {
+                                    app.preview_scroll = app.preview_scroll.saturating_add(1);
                                 }
-                                crate::app::PreviewMode::Combined => {
-                                    crate::app::PreviewMode::Highlighted
+                            }
+                            Some(Action::ScrollPreviewUp) => {
+                                app.preview_scroll = app.preview_scroll.saturating_sub(1);
+                            }
+                            Some(Action::MoveSelectionDown) => app.move_highlighted_selection(1),
+                            Some(Action::MoveSelectionUp) => app.move_highlighted_selection(-1),
+                            Some(Action::Down) => app.next(),
+                            Some(Action::Up) => app.previous(),
+                            Some(action @ (Action::ToggleSelect | Action::SelectAndPreview)) => {
+                                app.toggle_selection();
+                                if action == Action::SelectAndPreview {
+                                    app.show_combined_preview();
                                 }
-                            };
-                            app.preview_scroll = 0;
-                        }
-                        KeyCode::PageDown => {
-                            let max_scroll = app.max_preview_scroll();
-                            let target = app.preview_scroll.saturating_add(10);
-                            app.preview_scroll = target.min(max_scroll);
-                        }
-                        KeyCode::PageUp => {
-                            app.preview_scroll = app.preview_scroll.saturating_sub(10);
-                        }
-                        KeyCode::Enter => {
-                            // Save and Quit
-                            if !app.selected_templates.is_empty() {
+                                let missing = app.take_missing_selected_contents();
+                                if !missing.is_empty() {
+                                    spawn_content_fetch(client.clone(), tx.clone(), missing);
+                                }
+                            }
+                            Some(Action::DeselectFiltered) => {
+                                let removed = app.deselect_filtered();
+                                app.error = None;
+                                app.notification = Some(format!("Removed {}", removed));
+                            }
+                            Some(Action::Retry) if app.has_failed_contents() => {
+                                app.error = None;
+                                let failed = app.take_failed_contents();
+                                spawn_content_fetch(client.clone(), tx.clone(), failed);
+                            }
+                            Some(Action::Retry) if !app.is_loading => retry_fetch(&mut app, &client, &tx),
+                            Some(Action::ClearCache) if !app.is_loading => {
                                 app.notification = None;
                                 app.error = None;
-                                app.should_quit_after_save = true;
-                                if app.gitignore_exists() {
-                                    app.input_mode = InputMode::Confirm;
-                                    app.confirm_action = Some(crate::app::ConfirmAction::Append);
-                                } else {
-                                    let content = app.generate_gitignore_content();
-                                    if gitignore::write_gitignore(&app.gitignore_path(), &content, gitignore::WriteMode::Overwrite).is_ok() {
-                                        break 'main_loop;
+                                match client.clear_cache() {
+                                    Ok(()) => retry_fetch(&mut app, &client, &tx),
+                                    Err(e) => app.error = Some(AppError::System(format!("Failed to clear cache: {:#}", e))),
+                                }
+                            }
+                            Some(Action::PrefixSearch) => app.toggle_prefix_search(),
+                            Some(Action::TogglePreviewMode) => {
+                                app.preview_mode = match app.preview_mode {
+                                    app::PreviewMode::Highlighted => {
+                                        app::PreviewMode::Combined
+                                    }
+                                    app::PreviewMode::Combined => {
+                                        app::PreviewMode::Highlighted
                                     }
+                                };
+                                app.preview_scroll = 0;
+                            }
+                            Some(Action::Peek) => app.toggle_peek(),
+                            Some(Action::Zoom) => app.toggle_preview_zoom(),
+                            Some(Action::Jump) => app.start_jump(),
+                            Some(Action::Compare) => app.mark_compare(),
+                            Some(Action::CustomSection) => app.start_custom_section(),
+                            Some(Action::RefreshPreview) => app.refresh_preview(),
+                            Some(Action::Wrap) => app.toggle_preview_wrap(),
+                            Some(Action::Syntax) => app.preview_syntax = !app.preview_syntax,
+                            Some(Action::CycleSearchMode) => app.cycle_search_mode(),
+                            Some(Action::Pin) => app.toggle_pinned_preview(),
+                            Some(Action::Block) => {
+                                if let Err(e) = app.toggle_blocked_highlighted() {
+                                    app.error = Some(AppError::Config(format!("Failed to save config: {:#}", e)));
                                 }
-                            } else {
-                                app.error = Some("No templates selected!".to_string());
                             }
-                        }
-                        KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                            // Save
-                            if !app.selected_templates.is_empty() {
+                            Some(Action::ShowBlocked) => app.toggle_show_blocked(),
+                            Some(Action::CycleSource) => app.cycle_source_filter(),
+                            Some(Action::CompactList) => {
+                                if let Err(e) = app.toggle_compact_list() {
+                                    app.error = Some(AppError::Config(format!("Failed to save config: {:#}", e)));
+                                }
+                            }
+                            Some(Action::OpenDir) => {
                                 app.notification = None;
                                 app.error = None;
-                                app.should_quit_after_save = false;
-                                if app.gitignore_exists() {
-                                    app.input_mode = InputMode::Confirm;
-                                    app.confirm_action = Some(crate::app::ConfirmAction::Append);
+                                let dir = &app.output_dirs[0];
+                                match opener::open_dir(dir) {
+                                    Ok(()) => app.notification = Some(format!("Opened {}", dir.display())),
+                                    Err(e) => app.error = Some(AppError::System(format!("{:#}", e))),
+                                }
+                            }
+                            Some(Action::StripComments) => {
+                                app.strip_comments = !app.strip_comments;
+                                app.notification = Some(if app.strip_comments {
+                                    "Comments will be stripped from the generated file.".to_string()
                                 } else {
-                                    let content = app.generate_gitignore_content();
-                                    match gitignore::write_gitignore(&app.gitignore_path(), &content, gitignore::WriteMode::Overwrite) {
-                                        Ok(_) => app.notification = Some("Successfully created .gitignore!".to_string()),
-                                        Err(e) => app.error = Some(format!("Failed to write: {}", e)),
-                                    }
+                                    "Comments will be kept in the generated file.".to_string()
+                                });
+                            }
+                            Some(Action::ScrollRight) if !app.preview_wrap => {
+                                let max_hscroll = app.max_preview_hscroll();
+                                let target = app.preview_hscroll.saturating_add(4);
+                                app.preview_hscroll = target.min(max_hscroll);
+                            }
+                            Some(Action::ScrollLeft) if !app.preview_wrap => {
+                                app.preview_hscroll = app.preview_hscroll.saturating_sub(4);
+                            }
+                            Some(Action::PageDown) => {
+                                let max_scroll = app.max_preview_scroll();
+                                let target = app.preview_scroll.saturating_add(10);
+                                app.preview_scroll = target.min(max_scroll);
+                            }
+                            Some(Action::PageUp) => {
+                                app.preview_scroll = app.preview_scroll.saturating_sub(10);
+                            }
+                            Some(Action::Save | Action::SaveAndQuit) if app.read_only => {
+                                app.notification = None;
+                                app.error = Some(AppError::Input("Read-only mode: writing is disabled.".to_string()));
+                            }
+                            Some(Action::SaveAndQuit) => {
+                                let quit_after = app.config.enter_quits;
+                                let quit = request_save(&mut app, &client, quit_after);
+                                if quit {
+                                    break 'main_loop;
                                 }
-                            } else {
-                                app.error = Some("No templates selected!".to_string());
                             }
+                            Some(Action::Save) => {
+                                request_save(&mut app, &client, false);
+                            }
+                            Some(Action::QuickPick) => quick_pick(&mut app, &client),
+                            Some(_) => {}
+                            None => {
+                                if let KeyCode::Char(c @ '1'..='9') = key.code {
+                                    app.apply_saved_filter(c as usize - '1' as usize);
+                                }
+                            }
+                        },
+                    },
+                    InputMode::Help => match key.code {
+                        KeyCode::Down | KeyCode::Char('j') => app.help_scroll = app.help_scroll.saturating_add(1),
+                        KeyCode::Up | KeyCode::Char('k') => app.help_scroll = app.help_scroll.saturating_sub(1),
+                        _ => {
+                            app.help_scroll = 0;
+                            app.input_mode = InputMode::Normal;
                         }
+                    },
+                    InputMode::Jump => match key.code {
+                        KeyCode::Char(c) if c.is_alphanumeric() => app.jump_to_letter(c),
+                        _ => app.stop_jump(),
+                    },
+                    InputMode::CustomSection => match key.code {
+                        KeyCode::Char(c) => {
+                            if let Some(draft) = app.custom_draft.as_mut() {
+                                draft.input.push(c);
+                            }
+                        }
+                        KeyCode::Backspace => {
+                            if let Some(draft) = app.custom_draft.as_mut() {
+                                draft.input.pop();
+                            }
+                        }
+                        KeyCode::Enter => app.submit_custom_input(),
+                        KeyCode::Esc => app.cancel_custom_section(),
                         _ => {}
                     },
                     InputMode::Confirm => match key.code {
                         KeyCode::Char('a') | KeyCode::Left => {
-                            app.confirm_action = Some(crate::app::ConfirmAction::Append);
+                            app.confirm_action = Some(app::ConfirmAction::Append);
+                            app.overwrite_removals = None;
                         }
-                        KeyCode::Char('o') | KeyCode::Right => {
-                            app.confirm_action = Some(crate::app::ConfirmAction::Overwrite);
+                        KeyCode::Char('o') | KeyCode::Right if !app.config.never_overwrite => {
+                            app.confirm_action = Some(app::ConfirmAction::Overwrite);
                         }
-                        KeyCode::Enter => {
+                        KeyCode::Char('p') => app.toggle_append_preview(),
+                        KeyCode::Down | KeyCode::Char('j') => app.scroll_append_preview(1),
+                        KeyCode::Up | KeyCode::Char('k') => app.scroll_append_preview(-1),
+                        KeyCode::PageDown => app.scroll_append_preview(10),
+                        KeyCode::PageUp => app.scroll_append_preview(-10),
+                        KeyCode::Enter | KeyCode::Char('y') => {
+                            // `y` confirms immediately with the default action (Append).
+                            if key.code == KeyCode::Char('y') {
+                                app.confirm_action = Some(app::ConfirmAction::Append);
+                            }
                             let mode = match app.confirm_action {
-                                Some(crate::app::ConfirmAction::Append) => gitignore::WriteMode::Append,
+                                Some(app::ConfirmAction::Append) => gitignore::WriteMode::Append,
                                 _ => gitignore::WriteMode::Overwrite,
                             };
-                            let content = app.generate_gitignore_content();
-                            let should_quit = app.should_quit_after_save;
-                            match gitignore::write_gitignore(&app.gitignore_path(), &content, mode) {
-                                Ok(_) => {
-                                    if should_quit {
-                                        break 'main_loop;
-                                    }
-                                    app.notification = Some(format!(
-                                        "Successfully {}ed .gitignore!",
-                                        if let gitignore::WriteMode::Append = mode {
-                                            "append"
-                                        } else {
-                                            "overwrit"
-                                        }
-                                    ));
-                                    app.input_mode = InputMode::Normal;
-                                }
-                                Err(e) => {
-                                    app.error = Some(format!("Failed to write: {}", e));
-                                    app.input_mode = InputMode::Normal;
-                                }
+                            // A destructive overwrite needs a second Enter after seeing the count.
+                            if let gitignore::WriteMode::Overwrite = mode
+                                && app.overwrite_removals.is_none()
+                                && let Some(removed) = app.overwrite_removals_over_threshold()
+                            {
+                                app.overwrite_removals = Some(removed);
+                                continue;
+                            }
+                            app.overwrite_removals = None;
+                            if write_selection(&mut app, &client, mode) {
+                                break 'main_loop;
                             }
                         }
-                        KeyCode::Esc => {
+                        KeyCode::Esc | KeyCode::Char('n') => {
+                            app.overwrite_removals = None;
                             app.error = None;
                             app.notification = None;
                             app.input_mode = InputMode::Normal;
@@ -269,45 +583,229 @@ async fn main() -> Result<()> {
                     },
                 },
             }
+            if dirty {
+                app.mark_shown_seen();
+            }
         }
     }
 
+    // Leave the alternate screen first so the report stays in the scrollback.
+    drop(session);
+    if !args.quiet {
+        for line in &app.write_summary {
+            println!("{}", line);
+        }
+    }
     Ok(())
 }
 
-fn parse_output_dir() -> Result<PathBuf> {
-    let mut args = std::env::args().skip(1);
-    let mut output_dir: Option<PathBuf> = None;
+/// Writes the selection, or opens the append/overwrite prompt if a .gitignore already exists.
+/// Returns true when the app should exit (a direct write succeeded and `quit_after` is set).
+fn request_save(app: &mut App, client: &api::ApiClient, quit_after: bool) -> bool {
+    if app.selected_templates.is_empty() {
+        app.error = Some(AppError::Input("No templates selected!".to_string()));
+        return false;
+    }
+    app.notification = None;
+    app.error = None;
+    app.should_quit_after_save = quit_after;
+    if app.gitignore_exists() {
+        if app.assume_yes {
+            let mode = app.write_mode.unwrap_or(gitignore::WriteMode::Append);
+            return write_selection(app, client, mode);
+        }
+        app.input_mode = InputMode::Confirm;
+        app.confirm_action = Some(match app.write_mode {
+            Some(gitignore::WriteMode::Overwrite) => app::ConfirmAction::Overwrite,
+            _ => app::ConfirmAction::Append,
+        });
+        app.append_preview = None;
+        app.overwrite_removals = None;
+        return false;
+    }
+    let report = app.save(gitignore::WriteMode::Overwrite);
+    record_save(app, client, &report);
+    if let Some(error) = report.error() {
+        app.error = Some(AppError::Write(error));
+        return false;
+    }
+    // Stay open after an unlocked write so the warning is seen.
+    if let Some(notice) = fallback_notice(&report.written) {
+        app.notification = Some(notice);
+        return false;
+    }
+    if quit_after {
+        return true;
+    }
+    app.notification = Some("Successfully created .gitignore!".to_string());
+    false
+}
+
+/// Writes the selection over existing files in `mode`, reporting the result in the UI.
+/// Returns true when the app should exit (every write succeeded and a quit was requested).
+fn write_selection(app: &mut App, client: &api::ApiClient, mode: gitignore::WriteMode) -> bool {
+    let should_quit = app.should_quit_after_save;
+    app.input_mode = InputMode::Normal;
+    let report = app.save(mode);
+    record_save(app, client, &report);
+    if let Some(error) = report.error() {
+        app.error = Some(AppError::Write(error));
+        return false;
+    }
+    // Stay open after a fallback so the explanation is seen.
+    if let Some(notice) = fallback_notice(&report.written) {
+        app.notification = Some(notice);
+        return false;
+    }
+    if should_quit {
+        return true;
+    }
+    app.notification = Some(format!(
+        "Successfully {}ed .gitignore!",
+        if let gitignore::WriteMode::Append = mode {
+            "append"
+        } else {
+            "overwrit"
+        }
+    ));
+    false
+}
+
+/// Records the files a save did write (summary, usage and remembered directory), even when
+/// other targets failed.
+fn record_save(app: &mut App, client: &api::ApiClient, report: &app::SaveReport) {
+    if report.written.is_empty() {
+        return;
+    }
+    app.record_written(&report.written);
+    let _ = client.record_use(&app.get_selected_names());
+    let _ = app.remember_output_dir(&report.written_paths());
+}
+
+/// Explains any write that didn't go as requested (an overwrite fallback or a missing lock).
+fn fallback_notice(written: &[(std::path::PathBuf, gitignore::WriteOutcome)]) -> Option<String> {
+    let notices: Vec<String> = written
+        .iter()
+        .filter_map(|(path, outcome)| outcome.notice(path))
+        .collect();
+    (!notices.is_empty()).then(|| notices.join("; "))
+}
+
+/// Selects the top search match and goes straight to saving it (without quitting).
+fn quick_pick(app: &mut App, client: &api::ApiClient) {
+    if app.read_only {
+        app.notification = None;
+        app.error = Some(AppError::Input("Read-only mode: writing is disabled.".to_string()));
+        return;
+    }
+    let Some(name) = app.select_top_match() else {
+        app.notification = None;
+        app.error = Some(AppError::Input("No matching templates to pick.".to_string()));
+        return;
+    };
+    app.input_mode = InputMode::Normal;
+    request_save(app, client, false);
+    if app.error.is_none() {
+        let action = if app.input_mode == InputMode::Confirm {
+            "choose how to write .gitignore"
+        } else {
+            "wrote .gitignore"
+        };
+        app.notification = Some(format!("Quick pick: selected {}{}{}", name, app.symbols.dash, action));
+    }
+}
+
+/// Clears any error and starts a new full sync.
+fn retry_fetch(app: &mut App, client: &Arc<api::ApiClient>, tx: &mpsc::Sender<AppEvent>) {
+    app.notification = None;
+    app.error = None;
+    app.refresh_failed = false;
+    app.is_loading = true;
+    spawn_fetch(client.clone(), tx.clone());
+}
 
-    while let Some(arg) = args.next() {
-        match arg.as_str() {
-            "-d" | "--dir" => {
-                let value = args
-                    .next()
-                    .ok_or_else(|| anyhow::anyhow!("--dir requires a path"))?;
-                output_dir = Some(PathBuf::from(value));
+/// Runs a full sync in the background, reporting the result (or a timeout) through the channel.
+fn spawn_fetch(client: Arc<api::ApiClient>, tx: mpsc::Sender<AppEvent>) {
+    tokio::spawn(async move {
+        match tokio::time::timeout(FETCH_TIMEOUT, client.fetch_all_data()).await {
+            Ok(Ok(cache)) => {
+                let saved = client.save_cache(&cache);
+                let _ = tx.send(AppEvent::DataLoaded(cache)).await;
+                report_cache_save(&tx, saved).await;
+            }
+            Ok(Err(e)) => {
+                let _ = tx.send(AppEvent::Error(AppError::from_fetch(&e))).await;
+            }
+            Err(_) => {
+                let _ = tx
+                    .send(AppEvent::Error(AppError::Network(format!(
+                        "Fetching templates timed out after {}s.",
+                        FETCH_TIMEOUT.as_secs()
+                    ))))
+                    .await;
+            }
+        }
+    });
+}
+
+/// Refreshes the template data in the background while cached data is shown.
+/// Failures are reported quietly since the cached list remains usable.
+fn spawn_refresh(client: Arc<api::ApiClient>, tx: mpsc::Sender<AppEvent>) {
+    tokio::spawn(async move {
+        match tokio::time::timeout(FETCH_TIMEOUT, client.fetch_all_data()).await {
+            Ok(Ok(cache)) => {
+                let saved = client.save_cache(&cache);
+                let _ = tx.send(AppEvent::Refreshed(cache)).await;
+                report_cache_save(&tx, saved).await;
             }
             _ => {
-                if output_dir.is_some() {
-                    return Err(anyhow::anyhow!("Unexpected argument: {}", arg));
-                }
-                output_dir = Some(PathBuf::from(arg));
+                let _ = tx.send(AppEvent::RefreshFailed).await;
             }
         }
-    }
+    });
+}
 
-    let cwd = std::env::current_dir()?;
-    let dir = output_dir.map_or(cwd.clone(), |path| {
-        if path.is_absolute() {
-            path
-        } else {
-            cwd.join(path)
+/// Fetches contents for the given `(name, slug)` templates in the background, reporting
+/// per-template failures.
+fn spawn_content_fetch(
+    client: Arc<api::ApiClient>,
+    tx: mpsc::Sender<AppEvent>,
+    targets: Vec<(String, String)>,
+) {
+    tokio::spawn(async move {
+        let mut contents = HashMap::new();
+        let mut failures = Vec::new();
+        // Retry failures once so a transient error doesn't leave a template unavailable.
+        let (ok, failed): (Vec<_>, Vec<_>) = client
+            .fetch_templates(&targets)
+            .await
+            .into_iter()
+            .partition(|(_, result)| result.is_ok());
+        let retry: Vec<(String, String)> = targets
+            .into_iter()
+            .filter(|(name, _)| failed.iter().any(|(failed, _)| failed == name))
+            .collect();
+        let mut results = ok;
+        if !retry.is_empty() {
+            results.extend(client.fetch_templates(&retry).await);
         }
+        for (name, result) in results {
+            match result {
+                Ok(content) => {
+                    contents.insert(name, content);
+                }
+                Err(e) => failures.push((name, e.to_string())),
+            }
+        }
+        let saved = client.store_contents(&contents);
+        let _ = tx.send(AppEvent::ContentsLoaded(contents, failures)).await;
+        report_cache_save(&tx, saved).await;
     });
+}
 
-    if !dir.is_dir() {
-        return Err(anyhow::anyhow!("Target path is not a directory: {}", dir.display()));
+/// Reports a failed cache write to the UI; the data already in memory stays usable.
+async fn report_cache_save(tx: &mpsc::Sender<AppEvent>, saved: Result<()>) {
+    if let Err(e) = saved {
+        let _ = tx.send(AppEvent::CacheSaveFailed(format!("{:#}", e))).await;
     }
-
-    Ok(dir)
 }
@@ -8,4 +8,13 @@ pub struct CacheData {
     pub templates: Vec<String>,
     /// Map of template names to their respective .gitignore content.
     pub contents: HashMap<String, String>,
+    /// Unix time each template's content was last fetched or written, for LRU eviction.
+    #[serde(default)]
+    pub last_used: HashMap<String, u64>,
+    /// API key (slug) of each template by name, e.g. `visualstudiocode` for `VisualStudioCode`.
+    #[serde(default)]
+    pub slugs: HashMap<String, String>,
+    /// Templates dropped from a fresh fetch because their content was too large (not persisted).
+    #[serde(skip)]
+    pub oversized: Vec<String>,
 }
@@ -0,0 +1,26 @@
+use anyhow::Result;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Reveals `dir` in the platform's file manager without waiting for it to exit.
+pub fn open_dir(dir: &Path) -> Result<()> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(target_os = "windows") {
+        "explorer"
+    } else {
+        if std::env::var_os("DISPLAY").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_none() {
+            return Err(anyhow::anyhow!("No graphical session available to open {}", dir.display()));
+        }
+        "xdg-open"
+    };
+
+    Command::new(opener)
+        .arg(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Couldn't launch {}: {}", opener, e))?;
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use std::fs;
+use std::path::Path;
+
+/// Name of the optional per-project file listing templates to preselect.
+pub const DEFAULTS_FILE: &str = ".autogitignore";
+
+/// Parses a template list: one name per line, blank lines and `#` comment lines ignored.
+/// Only a leading `#` starts a comment, since names such as `c#` contain one.
+pub fn parse_name_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty() && !name.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Reads the project defaults file from `dir`, if one exists and is readable.
+pub fn load_defaults(dir: &Path) -> Option<Vec<String>> {
+    let content = fs::read_to_string(dir.join(DEFAULTS_FILE)).ok()?;
+    Some(parse_name_list(&content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_leading_hash_starts_a_comment() {
+        let text = "# project templates\nrust\n\n  # editors\nc#\n  VisualStudio  \n";
+        assert_eq!(parse_name_list(text), ["rust", "c#", "VisualStudio"]);
+    }
+}
@@ -0,0 +1,80 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// A shareable selection: template names (or URLs) plus the custom sections they need.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Recipe {
+    /// Template names or http(s) template URLs, as accepted by `--gen`.
+    pub templates: Vec<String>,
+    /// Custom section name -> content, for selected sections that are not upstream templates.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_sections: BTreeMap<String, String>,
+}
+
+impl Recipe {
+    /// Reads a recipe from `path`, or from stdin when `path` is `-`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = if path == Path::new("-") {
+            std::io::read_to_string(std::io::stdin()).context("Failed to read the selection from stdin")?
+        } else {
+            fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?
+        };
+        serde_json::from_str(&text).with_context(|| format!("Invalid selection file {}", path.display()))
+    }
+
+    /// Writes the recipe as pretty JSON to `path`, or to stdout when `path` is `-`.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)? + "\n";
+        if path == Path::new("-") {
+            print!("{}", json);
+            return Ok(());
+        }
+        fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recipe_round_trips_through_a_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("selection.json");
+        let recipe = Recipe {
+            templates: vec!["Rust".to_string(), "https://example.com/my.gitignore".to_string()],
+            custom_sections: BTreeMap::from([("Local".to_string(), "*.local".to_string())]),
+        };
+        recipe.save(&path).unwrap();
+
+        let loaded = Recipe::load(&path).unwrap();
+        assert_eq!(loaded.templates, recipe.templates);
+        assert_eq!(loaded.custom_sections, recipe.custom_sections);
+    }
+
+    #[test]
+    fn custom_sections_are_optional() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("selection.json");
+        fs::write(&path, r#"{"templates": ["Go"]}"#).unwrap();
+        let loaded = Recipe::load(&path).unwrap();
+        assert_eq!(loaded.templates, ["Go"]);
+        assert!(loaded.custom_sections.is_empty());
+
+        Recipe { templates: loaded.templates, ..Recipe::default() }.save(&path).unwrap();
+        assert!(!fs::read_to_string(&path).unwrap().contains("custom_sections"));
+    }
+
+    #[test]
+    fn invalid_files_name_the_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("selection.json");
+        fs::write(&path, r#"{"templates": "Go"}"#).unwrap();
+        let err = Recipe::load(&path).unwrap_err();
+        assert!(err.to_string().contains("Invalid selection file"), "{err}");
+        assert!(Recipe::load(&dir.path().join("missing.json")).is_err());
+    }
+}
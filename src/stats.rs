@@ -0,0 +1,54 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::project_dirs;
+
+/// Location of the local usage tallies in the OS data directory.
+pub fn path() -> Result<PathBuf> {
+    Ok(project_dirs()?.data_dir().join("stats.json"))
+}
+
+/// How many times each template has been written, or an empty map when nothing is recorded yet.
+pub fn load() -> Result<HashMap<String, u64>> {
+    let stats_path = path()?;
+    match fs::read_to_string(&stats_path) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| format!("Invalid stats file: {}", stats_path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", stats_path.display())),
+    }
+}
+
+/// Adds one use to each of `templates`. The file stays on this machine; nothing is sent anywhere.
+pub fn record(templates: &[String]) -> Result<()> {
+    let stats_path = path()?;
+    let mut counts = load().unwrap_or_default();
+    for name in templates {
+        *counts.entry(name.clone()).or_insert(0) += 1;
+    }
+    if let Some(dir) = stats_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&stats_path, serde_json::to_string_pretty(&counts)?)
+        .with_context(|| format!("Failed to write {}", stats_path.display()))
+}
+
+/// Prints the tallies, most used first (`--stats`).
+pub fn print() -> Result<()> {
+    let counts = load()?;
+    if counts.is_empty() {
+        println!(
+            "No usage recorded yet ({}); set \"record_stats\": true in the config to start",
+            path()?.display()
+        );
+        return Ok(());
+    }
+    let mut counts: Vec<(String, u64)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    for (name, count) in counts {
+        println!("{}\t{}", count, name);
+    }
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use ratatui::symbols::{border, scrollbar};
+
+/// Glyphs used when drawing the TUI, so a plain-ASCII terminal can swap all of them at once.
+pub struct Symbols {
+    /// Marker in front of the highlighted list row.
+    pub highlight: &'static str,
+    /// Compact-list marker for a selected template.
+    pub selected: &'static str,
+    /// Compact-list marker for an unselected template.
+    pub unselected: &'static str,
+    /// Appended to text cut short to fit its column.
+    pub ellipsis: &'static str,
+    /// Separator between header segments.
+    pub dash: &'static str,
+    /// Frames cycled through while templates are loading.
+    pub spinner: &'static [&'static str],
+    /// Pane and modal borders.
+    pub border: border::Set<'static>,
+    /// Preview scrollbar.
+    pub scrollbar: scrollbar::Set<'static>,
+}
+
+pub const UNICODE: Symbols = Symbols {
+    highlight: "▶ ",
+    selected: "● ",
+    unselected: "○ ",
+    ellipsis: "…",
+    dash: " — ",
+    spinner: &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+    border: border::PLAIN,
+    scrollbar: scrollbar::DOUBLE_VERTICAL,
+};
+
+pub const ASCII: Symbols = Symbols {
+    highlight: "> ",
+    selected: "* ",
+    unselected: "o ",
+    ellipsis: "~",
+    dash: " - ",
+    spinner: &["|", "/", "-", "\\"],
+    border: border::Set {
+        top_left: "+",
+        top_right: "+",
+        bottom_left: "+",
+        bottom_right: "+",
+        vertical_left: "|",
+        vertical_right: "|",
+        horizontal_top: "-",
+        horizontal_bottom: "-",
+    },
+    scrollbar: scrollbar::Set {
+        track: "|",
+        thumb: "#",
+        begin: "^",
+        end: "v",
+    },
+};
+
+/// Picks the ASCII set when forced, or when the terminal or locale can't be trusted with Unicode.
+pub fn select(force_ascii: bool) -> &'static Symbols {
+    if force_ascii || !unicode_terminal() {
+        &ASCII
+    } else {
+        &UNICODE
+    }
+}
+
+/// Whether the terminal looks able to render Unicode: `TERM` is not a bare console and the
+/// locale (`LC_ALL`, then `LC_CTYPE`, then `LANG`) names UTF-8.
+fn unicode_terminal() -> bool {
+    let term = std::env::var("TERM").unwrap_or_default();
+    if matches!(term.as_str(), "dumb" | "linux" | "vt100" | "vt102" | "vt220") {
+        return false;
+    }
+    if cfg!(windows) {
+        return true;
+    }
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    locale.contains("utf-8") || locale.contains("utf8")
+}
@@ -1,16 +1,31 @@
 use ratatui::{
     layout::Alignment,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{
+        Block, Borders, List, ListItem, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Wrap,
+    },
     Frame,
 };
 
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::api::PROVIDER_NAME;
 use crate::app::{App, InputMode};
+use crate::error::AppError;
+use crate::keymap::{Action, FIXED_KEYS};
+use crate::symbols::Symbols;
 
 /// Main entry point for drawing the TUI. Dispatches to individual pane drawers.
 pub fn draw(f: &mut Frame, app: &mut App) {
+    if app.preview_zoomed {
+        draw_preview_pane(f, app, f.area());
+        draw_modal(f, app);
+        return;
+    }
+
     let vertical_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints(
@@ -18,18 +33,35 @@ pub fn draw(f: &mut Frame, app: &mut App) {
                 Constraint::Length(3), // Header
                 Constraint::Min(10),   // Main Content (List + Preview)
                 Constraint::Length(3), // Search
-                Constraint::Length(5), // Status/Selected/Shortcuts
+                Constraint::Length(6), // Status/Selected/Shortcuts
             ]
             .as_ref(),
         )
         .split(f.area());
 
-    // Header
-    let header = Paragraph::new("Welcome to autogitignore")
+    // Header: data source and template count
+    let sym = app.symbols;
+    let mut count = if app.is_loading && app.templates.is_empty() {
+        format!("{} loading", sym.spinner[app.spinner_frame % sym.spinner.len()])
+    } else if app.local_templates.is_empty() {
+        format!("{} templates", app.templates.len())
+    } else {
+        format!("{} templates ({} local)", app.templates.len(), app.local_templates.len())
+    };
+    if app.refreshing {
+        count.push_str(&format!("{}refreshing {}", sym.dash, sym.spinner[app.spinner_frame % sym.spinner.len()]));
+    } else if app.refresh_failed {
+        count.push_str(&format!("{}cached (refresh failed)", sym.dash));
+    }
+    if app.remembered_output_dir {
+        count.push_str(&format!("{}into {} (last used)", sym.dash, app.output_dirs[0].display()));
+    }
+    let header = Paragraph::new(format!("autogitignore{}{}{}{}", sym.dash, PROVIDER_NAME, sym.dash, count))
         .style(Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD))
         .block(
             Block::default()
                 .borders(Borders::ALL)
+                .border_set(app.symbols.border)
                 .border_style(Style::default().fg(Color::Magenta)),
         )
         .alignment(Alignment::Center);
@@ -50,79 +82,229 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     // Status / Selected
     draw_status_pane(f, app, vertical_chunks[3]);
 
-    if let InputMode::Confirm = app.input_mode {
-        draw_confirm_modal(f, app);
+    draw_modal(f, app);
+}
+
+/// Draws the popup for the current input mode, if it has one.
+fn draw_modal(f: &mut Frame, app: &mut App) {
+    match app.input_mode {
+        InputMode::Confirm => draw_confirm_modal(f, app),
+        InputMode::Help => draw_help_modal(f, app),
+        _ => {}
     }
 }
 
 /// Renders the left pane containing the list of filtered templates.
+/// In compact mode rows use a bullet marker and, when there is room, flow into two columns
+/// (top to bottom, then left to right) so a page holds twice as many templates.
 fn draw_list_pane(f: &mut Frame, app: &mut App, area: Rect) {
-    let items: Vec<ListItem> = if app.is_loading && app.filtered_templates.is_empty() {
-        vec![ListItem::new("Fetching templates from gitignore.io...")
-            .style(Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC))]
-    } else if app.filtered_templates.is_empty() {
-        vec![ListItem::new("No templates found.").style(Style::default().fg(Color::Yellow))]
+    let compact = app.config.compact_list;
+    let mut title = " Matching Templates ".to_string();
+    if app.source_filter != crate::app::SourceFilter::All {
+        title.push_str(&format!("[{}] ", app.source_filter.label()));
+    }
+    if compact {
+        title.push_str("(compact) ");
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(app.symbols.border)
+        .title(title)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if app.is_loading && app.filtered_templates.is_empty() {
+        let item = ListItem::new("Fetching templates from gitignore.io...")
+            .style(Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC));
+        f.render_widget(List::new([item]), inner);
+        return;
+    }
+    if app.filtered_templates.is_empty() {
+        let item = ListItem::new("No templates found.").style(Style::default().fg(Color::Yellow));
+        f.render_widget(List::new([item]), inner);
+        return;
+    }
+
+    let total = app.filtered_templates.len();
+    let rows = inner.height as usize;
+    // Two-line content-match rows don't fit the column paging, so they use the single list.
+    if compact && app.content_matches.is_empty() && inner.width >= 40 && rows > 0 && total > rows {
+        let page = rows * 2;
+        let start = app.highlighted_index / page * page;
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(inner);
+        for (col, column_area) in columns.iter().enumerate() {
+            let from = start + col * rows;
+            if from >= total {
+                break;
+            }
+            let to = (from + rows).min(total);
+            let highlighted = (from..to)
+                .contains(&app.highlighted_index)
+                .then(|| app.highlighted_index - from);
+            render_template_list(f, app, from..to, highlighted, *column_area);
+        }
     } else {
-        app.filtered_templates
-            .iter()
-            .map(|t| {
-                let is_selected = app.selected_templates.contains(t);
-                let content = if is_selected {
-                    format!("[X] {}", t)
-                } else {
-                    format!("[ ] {}", t)
-                };
+        render_template_list(f, app, 0..total, Some(app.highlighted_index), inner);
+    }
+}
 
-                let style = if is_selected {
-                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default()
-                };
-                ListItem::new(content).style(style)
-            })
-            .collect()
-    };
+/// Renders `filtered_templates[range]` as a list, highlighting the `highlighted` row within it.
+fn render_template_list(
+    f: &mut Frame,
+    app: &App,
+    range: std::ops::Range<usize>,
+    highlighted: Option<usize>,
+    area: Rect,
+) {
+    let compact = app.config.compact_list;
+    let items: Vec<ListItem> = app.filtered_templates[range]
+        .iter()
+        .map(|&i| &app.templates[i])
+        .map(|t| {
+            let is_selected = app.selected_templates.contains(t);
+            let marker = match (compact, is_selected) {
+                (true, true) => app.symbols.selected,
+                (true, false) => app.symbols.unselected,
+                (false, true) => "[X] ",
+                (false, false) => "[ ] ",
+            };
+
+            let style = if is_selected {
+                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+
+            let mut spans = vec![Span::raw(marker)];
+            spans.extend(highlight_matches(app, t));
+            if app.local_templates.contains(t) {
+                spans.push(Span::styled(" (local)", Style::default().fg(Color::Cyan)));
+            }
+            if app.new_templates.contains(t) {
+                spans.push(Span::styled(
+                    " NEW",
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                ));
+            }
+            if app.custom_templates.contains(t) {
+                spans.push(Span::styled(" (custom)", Style::default().fg(Color::Magenta)));
+            }
+            if app.is_blocked(t) {
+                spans.push(Span::styled(" (blocked)", Style::default().fg(Color::DarkGray)));
+            }
+            let mut lines = vec![Line::from(spans)];
+            if let Some(line) = app.content_matches.get(t) {
+                // Content search: show why the template matched as a dim subtitle.
+                lines.push(Line::styled(
+                    format!("    matched: {}", line),
+                    Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+                ));
+            }
+            ListItem::new(lines).style(style)
+        })
+        .collect();
 
     let mut state = ListState::default();
-    if app.filtered_templates.is_empty() {
-        state.select(None);
-    } else {
-        state.select(Some(app.highlighted_index));
-    }
+    state.select(highlighted);
 
     let list = List::new(items)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(" Matching Templates ")
-                .border_style(Style::default().fg(Color::Cyan)),
-        )
         .highlight_style(
             Style::default()
                 .bg(Color::Blue)
                 .fg(Color::White)
                 .add_modifier(Modifier::BOLD),
         )
-        .highlight_symbol("▶ ");
+        .highlight_symbol(app.symbols.highlight);
 
     f.render_stateful_widget(list, area, &mut state);
 }
 
+/// Splits a template name into spans, emphasizing the characters matched by the search query.
+/// Built from spans (not a pre-formatted string) so styling survives wrapping and clipping.
+fn highlight_matches(app: &App, name: &str) -> Vec<Span<'static>> {
+    let indices = app.match_indices(name);
+    if indices.is_empty() {
+        return vec![Span::raw(name.to_string())];
+    }
+
+    let match_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, c) in name.chars().enumerate() {
+        let matched = indices.contains(&i);
+        if matched != run_matched && !run.is_empty() {
+            let text = std::mem::take(&mut run);
+            spans.push(if run_matched {
+                Span::styled(text, match_style)
+            } else {
+                Span::raw(text)
+            });
+        }
+        run_matched = matched;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(if run_matched {
+            Span::styled(run, match_style)
+        } else {
+            Span::raw(run)
+        });
+    }
+    spans
+}
+
 /// Renders the right pane showing the preview of highlighted or combined templates.
 fn draw_preview_pane(f: &mut Frame, app: &mut App, area: Rect) {
     let mode_str = match app.preview_mode {
+        _ if app.compared_pair().is_some() => " [COMPARE] ",
         crate::app::PreviewMode::Highlighted => " [HIGHLIGHT] ",
         crate::app::PreviewMode::Combined => " [COMBINED] ",
     };
 
-    let title = format!(" Preview {} ", mode_str);
+    let pin_str = match (&app.preview_mode, &app.pinned_preview) {
+        (crate::app::PreviewMode::Highlighted, Some(_)) => "[PINNED] ",
+        _ => "",
+    };
+    let wrap_str = if app.preview_wrap { "" } else { "[NOWRAP] " };
+    let zoom_str = if app.preview_zoomed { "[ZOOM: z to restore] " } else { "" };
+    let peek_str = match app.preview_mode {
+        crate::app::PreviewMode::Combined if app.peek => "[PEEK] ",
+        _ => "",
+    };
     let content = app.get_combined_preview();
     let content_height = area.height.saturating_sub(2);
-    app.set_preview_height(content_height);
-    let preview = Paragraph::new(content)
+    let content_width = area.width.saturating_sub(2);
+    app.set_preview_size(content_width, content_height);
+
+    let max_scroll = app.max_preview_scroll();
+    let scroll_str = if max_scroll > 0 {
+        format!("{}% ", app.preview_scroll as usize * 100 / max_scroll as usize)
+    } else {
+        String::new()
+    };
+    let title = format!(
+        " Preview {}{}{}{}{}{}",
+        mode_str, pin_str, peek_str, wrap_str, zoom_str, scroll_str
+    );
+    let text: Vec<Line> = if app.compared_pair().is_some() {
+        highlight_diff(&content)
+    } else if app.preview_syntax {
+        highlight_gitignore(&content)
+    } else {
+        content.lines().map(|line| Line::from(line.to_string())).collect()
+    };
+    let mut preview = Paragraph::new(text)
         .block(
             Block::default()
                 .borders(Borders::ALL)
+                .border_set(app.symbols.border)
                 .title(Span::styled(
                     title,
                     Style::default()
@@ -131,27 +313,101 @@ fn draw_preview_pane(f: &mut Frame, app: &mut App, area: Rect) {
                 ))
                 .border_style(Style::default().fg(Color::Yellow)),
         )
-        .wrap(Wrap { trim: false })
-        .scroll((app.preview_scroll, 0));
+        .scroll((app.preview_scroll, app.preview_hscroll));
+    if app.preview_wrap {
+        preview = preview.wrap(Wrap { trim: false });
+    }
 
     f.render_widget(preview, area);
+
+    if max_scroll > 0 {
+        let mut state = ScrollbarState::new(max_scroll as usize).position(app.preview_scroll as usize);
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .symbols(app.symbols.scrollbar.clone())
+            .style(Style::default().fg(Color::Yellow));
+        f.render_stateful_widget(
+            scrollbar,
+            area.inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut state,
+        );
+    }
+}
+
+/// Colors a unified diff: additions green, removals red, hunk headers cyan.
+fn highlight_diff(content: &str) -> Vec<Line<'static>> {
+    content
+        .lines()
+        .map(|line| {
+            let style = if line.starts_with("+++") || line.starts_with("---") {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else if line.starts_with('+') {
+                Style::default().fg(Color::Green)
+            } else if line.starts_with('-') {
+                Style::default().fg(Color::Red)
+            } else if line.starts_with("@@") {
+                Style::default().fg(Color::Cyan)
+            } else {
+                Style::default()
+            };
+            Line::styled(line.to_string(), style)
+        })
+        .collect()
+}
+
+/// Colors .gitignore syntax: comments dim, negations and directory patterns distinct.
+fn highlight_gitignore(content: &str) -> Vec<Line<'static>> {
+    content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim();
+            let style = if trimmed.starts_with('#') {
+                Style::default().fg(Color::DarkGray)
+            } else if trimmed.starts_with('!') {
+                Style::default().fg(Color::Green)
+            } else if trimmed.ends_with('/') {
+                Style::default().fg(Color::Cyan)
+            } else {
+                Style::default()
+            };
+            Line::styled(line.to_string(), style)
+        })
+        .collect()
 }
 
 /// Renders the search input field.
 fn draw_search_pane(f: &mut Frame, app: &mut App, area: Rect) {
+    if let Some(draft) = &app.custom_draft {
+        draw_custom_section_input(f, draft, app.symbols, area);
+        return;
+    }
     let input_style = if let InputMode::Editing = app.input_mode {
         Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
     } else {
         Style::default().fg(Color::DarkGray)
     };
 
-    let title = if let InputMode::Editing = app.input_mode {
-        Span::styled(" Search (Typing...) ", Style::default().fg(Color::Cyan))
+    let mode = app.search_mode.label();
+    let filter = app
+        .active_filter_name()
+        .map(|name| format!(" [filter: {}]", name))
+        .unwrap_or_default();
+    let title = if let Some(err) = &app.search_error {
+        Span::styled(format!(" Search [{}]{} {} ", mode, filter, err), Style::default().fg(Color::LightRed))
+    } else if let InputMode::Editing = app.input_mode {
+        let hint = match app.keymap.label(Action::CycleSearchMode) {
+            Some(keys) => format!("Typing... {} to change mode", keys),
+            None => "Typing...".to_string(),
+        };
+        Span::styled(format!(" Search [{}]{} ({}) ", mode, filter, hint), Style::default().fg(Color::Cyan))
     } else {
-        Span::styled(
-            " Search (Press '/' or 'i' to browse) ",
-            Style::default().fg(Color::DarkGray),
-        )
+        let hint = match app.keymap.label(Action::Search) {
+            Some(keys) => format!(" (Press {} to browse)", keys),
+            None => String::new(),
+        };
+        Span::styled(format!(" Search [{}]{}{} ", mode, filter, hint), Style::default().fg(Color::DarkGray))
     };
 
     let input = Paragraph::new(app.search_query.as_str())
@@ -159,23 +415,60 @@ fn draw_search_pane(f: &mut Frame, app: &mut App, area: Rect) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
+                .border_set(app.symbols.border)
                 .title(title)
                 .border_style(input_style),
         );
     f.render_widget(input, area);
 
     if let InputMode::Editing = app.input_mode {
-        let cursor_x = area.x.saturating_add(1).saturating_add(app.search_query.len() as u16);
+        let query_width = app.search_query.width().min(u16::MAX as usize) as u16;
+        let cursor_x = area.x.saturating_add(1).saturating_add(query_width);
         let max_x = area.x.saturating_add(area.width.saturating_sub(1));
         let cursor_x = cursor_x.min(max_x);
         f.set_cursor_position((cursor_x, area.y + 1));
     }
 }
 
+/// Renders the custom section input in place of the search box.
+fn draw_custom_section_input(f: &mut Frame, draft: &crate::app::CustomDraft, symbols: &Symbols, area: Rect) {
+    let style = Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD);
+    let title = match &draft.name {
+        None => " New custom section: name (Enter to continue, Esc to cancel) ".to_string(),
+        Some(name) => format!(
+            " Custom section {}: line {} (Enter adds it; Enter on an empty line saves) ",
+            name,
+            draft.lines.len() + 1
+        ),
+    };
+    let input = Paragraph::new(draft.input.as_str())
+        .style(style)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(symbols.border)
+                .title(title)
+                .border_style(style),
+        );
+    f.render_widget(input, area);
+
+    let input_width = draft.input.width().min(u16::MAX as usize) as u16;
+    let cursor_x = area.x.saturating_add(1).saturating_add(input_width);
+    let max_x = area.x.saturating_add(area.width.saturating_sub(1));
+    f.set_cursor_position((cursor_x.min(max_x), area.y + 1));
+}
+
 /// Renders the bottom status bar including selected templates summary and key shortcuts.
 fn draw_status_pane(f: &mut Frame, app: &mut App, area: Rect) {
     let selected_count = app.selected_templates.len();
-    let selected_names = app.get_selected_names_summary();
+    let selected_names = app.get_selected_names();
+
+    // Rows left for the selection after the targets and shortcuts lines.
+    let content_width = area.width.saturating_sub(2) as usize;
+    let reserved_rows = 2;
+    let selection_rows = (area.height.saturating_sub(2) as usize)
+        .saturating_sub(reserved_rows)
+        .max(1);
 
     let mut status_lines = Vec::new();
 
@@ -193,19 +486,44 @@ fn draw_status_pane(f: &mut Frame, app: &mut App, area: Rect) {
             Span::styled(msg, Style::default().fg(Color::LightGreen)),
         ]));
     } else if let Some(err) = &app.error {
-        status_lines.push(Line::from(vec![
+        // Problems the user can fix in place are yellow; failures are red.
+        let (badge_bg, badge_fg, text_fg) = match err {
+            AppError::Input(_) | AppError::Config(_) => (Color::Yellow, Color::Black, Color::Yellow),
+            _ => (Color::Red, Color::White, Color::LightRed),
+        };
+        let mut spans = vec![
             Span::styled(
-                " ERROR ",
+                format!(" {} ", err.label()),
                 Style::default()
-                    .bg(Color::Red)
-                    .fg(Color::White)
+                    .bg(badge_bg)
+                    .fg(badge_fg)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(" "),
-            Span::styled(err, Style::default().fg(Color::LightRed)),
-        ]));
+            Span::styled(err.to_string(), Style::default().fg(text_fg)),
+        ];
+        if err.is_retryable()
+            && let Some(keys) = app.keymap.label(Action::Retry)
+        {
+            spans.push(Span::styled(
+                format!("  Press {} to retry", keys),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ));
+        }
+        status_lines.push(Line::from(spans));
     } else {
-        let mut spans = vec![
+        let mut spans = Vec::new();
+        if app.read_only {
+            spans.push(Span::styled(
+                " READ-ONLY ",
+                Style::default()
+                    .bg(Color::Yellow)
+                    .fg(Color::Black)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::raw(" "));
+        }
+        spans.extend([
             Span::styled(
                 format!(" SELECTED ({}): ", selected_count),
                 Style::default()
@@ -214,29 +532,74 @@ fn draw_status_pane(f: &mut Frame, app: &mut App, area: Rect) {
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(" "),
-        ];
+        ]);
 
         if selected_count > 0 {
-            spans.push(Span::styled(selected_names, Style::default().fg(Color::Green)));
+            let prefix_width: usize = spans.iter().map(|span| span.width()).sum();
+            let rows = wrap_names(
+                &selected_names,
+                content_width.saturating_sub(prefix_width),
+                content_width,
+                selection_rows,
+                app.symbols.ellipsis,
+            );
+            let name_style = Style::default().fg(Color::Green);
+            let mut rows = rows.into_iter();
+            if let Some(first) = rows.next() {
+                spans.push(Span::styled(first, name_style));
+            }
+            status_lines.push(Line::from(spans));
+            status_lines.extend(rows.map(|row| Line::from(Span::styled(row, name_style))));
         } else {
             spans.push(Span::styled("None", Style::default().fg(Color::DarkGray)));
+            status_lines.push(Line::from(spans));
         }
-        status_lines.push(Line::from(spans));
     }
 
-    status_lines.push(Line::from("")); // Spacer
+    // Always visible, so the destination is known before saving.
+    let targets = app.gitignore_display_paths();
+    status_lines.push(Line::from(vec![
+        Span::styled(
+            if targets.len() > 1 { " TARGETS: " } else { " TARGET: " },
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::raw(targets.join(", ")),
+    ]));
 
-    // Line 3: Shortcuts (Beautifully formatted)
-    let shortcuts = vec![
-        ("SPACE", "Select"),
-        ("/, I", "Search"),
-        ("ESC", "Exit Search"),
-        ("P", "Toggle Mode"),
-        ("ALT+J/K", "Scroll Preview"),
-        ("CTRL+S", "Save"),
-        ("ENTER", "Save&Quit"),
-        ("Q", "Quit"),
-    ];
+    // Line 3: Shortcuts for the current mode (Beautifully formatted)
+    let fixed = |keys: &[(&str, &'static str)]| -> Vec<(String, &'static str)> {
+        keys.iter().map(|(key, desc)| (key.to_string(), *desc)).collect()
+    };
+    let shortcuts: Vec<(String, &str)> = match app.input_mode {
+        InputMode::CustomSection => fixed(&[("ENTER", "Next Line / Save"), ("BACKSPACE", "Delete"), ("ESC", "Cancel")]),
+        InputMode::Jump => fixed(&[("A-Z, 0-9", "Jump / Next Match"), ("ESC", "Done")]),
+        InputMode::Help => fixed(&[("UP/DOWN", "Scroll"), ("ANY KEY", "Close")]),
+        InputMode::Editing => {
+            let mut keys = fixed(&[("TYPE", "Filter"), ("UP/DOWN", "Move"), ("ESC, ENTER", "Exit Search")]);
+            keys.extend(app.keymap.label(Action::CycleSearchMode).map(|key| (key, "Search Mode")));
+            keys.push(("TAB".to_string(), "Quick Pick"));
+            keys
+        }
+        _ => {
+            let highlighted_selected = app
+                .get_current_highlighted()
+                .is_some_and(|t| app.selected_templates.contains(&t));
+            [
+                (Action::ToggleSelect, if highlighted_selected { "Deselect" } else { "Select" }),
+                (Action::Search, "Search"),
+                (Action::TogglePreviewMode, "Toggle Mode"),
+                (Action::ScrollPreviewDown, "Scroll Preview"),
+                (Action::Save, "Save"),
+                (Action::SaveAndQuit, if app.config.enter_quits { "Save&Quit" } else { "Save&Stay" }),
+                (Action::QuickPick, "Quick Pick"),
+                (Action::Help, "All Keys"),
+                (Action::Quit, "Quit"),
+            ]
+            .into_iter()
+            .filter_map(|(action, desc)| Some((app.keymap.label(action)?, desc)))
+            .collect()
+        }
+    };
 
     let mut shortcut_spans = Vec::new();
     for (i, (key, desc)) in shortcuts.iter().enumerate() {
@@ -255,22 +618,114 @@ fn draw_status_pane(f: &mut Frame, app: &mut App, area: Rect) {
     status_lines.push(Line::from(shortcut_spans));
 
     let status = Paragraph::new(status_lines)
-        .block(Block::default().borders(Borders::ALL).title(" Info & Controls "));
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(app.symbols.border)
+                .title(" Info & Controls "),
+        );
     f.render_widget(status, area);
 }
 
+/// Packs comma-separated names into at most `max_rows` rows (the first row is `first_width` wide,
+/// the rest `width`), ending with "+N more" when not everything fits. Every row, the suffix row
+/// included, is cut to its width.
+fn wrap_names(names: &[String], first_width: usize, width: usize, max_rows: usize, ellipsis: &str) -> Vec<String> {
+    let row_width = |row: usize| if row == 0 { first_width } else { width };
+    let render = |row: &[&str]| row.join(", ");
+
+    let mut rows: Vec<Vec<&str>> = vec![Vec::new()];
+    for (i, name) in names.iter().enumerate() {
+        let last = rows.len() - 1;
+        let mut candidate = rows[last].clone();
+        candidate.push(name);
+        // Leave room for the trailing ", " separator unless this is the final name.
+        let needed = render(&candidate).width() + if i + 1 < names.len() { 2 } else { 0 };
+        if needed <= row_width(last) {
+            rows[last] = candidate;
+            continue;
+        }
+        if rows[last].is_empty() {
+            // A single name wider than the row is cut (on a character boundary) with an ellipsis.
+            rows[last] = vec![name];
+            continue;
+        }
+        if rows.len() == max_rows {
+            // Drop names from the last row until the "+N more" suffix fits.
+            let mut hidden = names.len() - i;
+            loop {
+                let suffix = format!("+{} more", hidden);
+                let shown = render(&rows[last]);
+                let text = if shown.is_empty() { suffix } else { format!("{}, {}", shown, suffix) };
+                if text.width() <= row_width(last) || rows[last].is_empty() {
+                    let mut out: Vec<String> = rows[..last].iter().map(|row| format!("{},", render(row))).collect();
+                    out.push(text);
+                    return fit_rows(out, row_width, ellipsis);
+                }
+                rows[last].pop();
+                hidden += 1;
+            }
+        }
+        rows.push(vec![name]);
+    }
+
+    let count = rows.len();
+    let out = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| if i + 1 < count { format!("{},", render(row)) } else { render(row) })
+        .collect();
+    fit_rows(out, row_width, ellipsis)
+}
+
+/// Cuts each row to its width (see `wrap_names`); a single name wider than the pane would overflow otherwise.
+fn fit_rows(rows: Vec<String>, row_width: impl Fn(usize) -> usize, ellipsis: &str) -> Vec<String> {
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| truncate_to_width(row, row_width(i), ellipsis))
+        .collect()
+}
+
+/// Cuts `text` to at most `max_width` terminal columns, ending with `ellipsis` (one column wide)
+/// when shortened. Works on whole characters, so multibyte and double-width text never splits mid-character.
+fn truncate_to_width(text: &str, max_width: usize, ellipsis: &str) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+    let budget = max_width.saturating_sub(1);
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > budget {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+    if max_width > 0 {
+        out.push_str(ellipsis);
+    }
+    out
+}
+
 /// Renders the centered confirmation modal for handling existing .gitignore files.
 fn draw_confirm_modal(f: &mut Frame, app: &mut App) {
     let area = f.area();
     let block = Block::default()
         .title(" .gitignore already exists! ")
         .borders(Borders::ALL)
+        .border_set(app.symbols.border)
         .border_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
 
-    let modal_area = centered_rect(50, 40, area);
+    let modal_area = if app.append_preview.is_some() {
+        centered_rect(80, 80, area)
+    } else {
+        centered_rect(50, 40, area)
+    };
     f.render_widget(ratatui::widgets::Clear, modal_area);
 
-    let text = vec![
+    let mut text = vec![
         Line::from(""),
         Line::from(vec![
             Span::raw("An existing "),
@@ -295,9 +750,9 @@ fn draw_confirm_modal(f: &mut Frame, app: &mut App) {
                     Style::default().fg(Color::Green)
                 },
             ),
-            Span::raw("    "),
+            Span::raw(if app.config.never_overwrite { "" } else { "    " }),
             Span::styled(
-                " [O] Overwrite ",
+                if app.config.never_overwrite { "" } else { " [O] Overwrite " },
                 if app.confirm_action == Some(crate::app::ConfirmAction::Overwrite) {
                     Style::default()
                         .bg(Color::Red)
@@ -311,22 +766,100 @@ fn draw_confirm_modal(f: &mut Frame, app: &mut App) {
         Line::from(""),
         Line::from(""),
         Line::from(vec![Span::styled(
-            " Use Left/Right Arrow or A/O to select, Enter to confirm ",
+            if app.config.never_overwrite {
+                " Overwrite is disabled (never_overwrite); Enter to append "
+            } else {
+                " Use Left/Right Arrow or A/O to select, Enter to confirm "
+            },
             Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
         )]),
         Line::from(""),
         Line::from(vec![Span::styled(
-            " Press [ESC] to cancel ",
+            if app.append_preview.is_some() {
+                " [Y] Append now  [P] Hide preview  [N]/[ESC] Cancel "
+            } else {
+                " [Y] Append now  [P] Preview append  [N]/[ESC] Cancel "
+            },
             Style::default().fg(Color::DarkGray),
         )]),
     ];
+    if let Some(removed) = app.overwrite_removals {
+        text.insert(
+            text.len() - 4,
+            Line::from(Span::styled(
+                format!(
+                    "This will remove {} lines not present in the new content. Press Enter again to overwrite.",
+                    removed
+                ),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )),
+        );
+    }
+
+    let Some(preview) = &app.append_preview else {
+        let paragraph = Paragraph::new(text)
+            .block(block)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        f.render_widget(paragraph, modal_area);
+        return;
+    };
+
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(text.len() as u16), Constraint::Min(3)])
+        .split(inner);
+    f.render_widget(
+        Paragraph::new(text).alignment(Alignment::Center).wrap(Wrap { trim: true }),
+        chunks[0],
+    );
+
+    let total_lines = preview.lines().count();
+    let preview_block = Block::default()
+        .title(format!(
+            " Result after append (line {}/{}, Up/Down to scroll) ",
+            (app.append_preview_scroll as usize + 1).min(total_lines),
+            total_lines
+        ))
+        .borders(Borders::TOP)
+        .border_set(app.symbols.border);
+    f.render_widget(
+        Paragraph::new(preview.as_str())
+            .block(preview_block)
+            .scroll((app.append_preview_scroll, 0)),
+        chunks[1],
+    );
+}
 
-    let paragraph = Paragraph::new(text)
-        .block(block)
-        .alignment(Alignment::Center)
-        .wrap(Wrap { trim: true });
+/// Lists every bound key from the keymap (so custom bindings show), then the fixed ones.
+fn draw_help_modal(f: &mut Frame, app: &mut App) {
+    let modal_area = centered_rect(70, 80, f.area());
+    f.render_widget(ratatui::widgets::Clear, modal_area);
 
-    f.render_widget(paragraph, modal_area);
+    let mut entries = app.keymap.help_lines();
+    entries.extend(FIXED_KEYS.iter().map(|(key, desc)| (key.to_string(), *desc)));
+    let key_width = entries.iter().map(|(key, _)| key.width()).max().unwrap_or(0);
+    let lines: Vec<Line> = entries
+        .into_iter()
+        .map(|(key, desc)| {
+            let padding = " ".repeat(key_width - key.width());
+            Line::from(vec![
+                Span::styled(format!(" {}{} ", key, padding), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::raw(format!(" {}", desc)),
+            ])
+        })
+        .collect();
+
+    let max_scroll = (lines.len() as u16).saturating_sub(modal_area.height.saturating_sub(2));
+    app.help_scroll = app.help_scroll.min(max_scroll);
+    let block = Block::default()
+        .title(" Keys (Up/Down to scroll, any other key to close) ")
+        .borders(Borders::ALL)
+        .border_set(app.symbols.border)
+        .border_style(Style::default().fg(Color::Cyan));
+    f.render_widget(Paragraph::new(lines).block(block).scroll((app.help_scroll, 0)), modal_area);
 }
 
 /// Helper function to create a centered rectangle for popups/modals.
@@ -355,3 +888,129 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         )
         .split(popup_layout[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Args;
+    use crate::config::{Config, SearchMode};
+
+    fn app_searching(mode: SearchMode, query: &str) -> App {
+        let mut app = App::new(&Args::default(), Config::default());
+        app.search_mode = mode;
+        app.search_query = query.to_string();
+        app
+    }
+
+    /// Each span's text, with the matched ones in brackets.
+    fn marked(spans: &[Span]) -> String {
+        spans
+            .iter()
+            .map(|span| {
+                if span.style.add_modifier.contains(Modifier::UNDERLINED) {
+                    format!("[{}]", span.content)
+                } else {
+                    span.content.to_string()
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn highlights_the_matched_characters() {
+        let app = app_searching(SearchMode::Substring, "UST");
+        assert_eq!(marked(&highlight_matches(&app, "Rust")), "R[ust]");
+        let app = app_searching(SearchMode::Prefix, "jet");
+        assert_eq!(marked(&highlight_matches(&app, "JetBrains")), "[Jet]Brains");
+        let app = app_searching(SearchMode::Fuzzy, "vsc");
+        assert_eq!(marked(&highlight_matches(&app, "VisualStudioCode")), "[V]isual[S]tudio[C]ode");
+    }
+
+    #[test]
+    fn highlights_by_character_not_byte() {
+        let app = app_searching(SearchMode::Substring, "fé");
+        assert_eq!(marked(&highlight_matches(&app, "Café")), "Ca[fé]");
+    }
+
+    #[test]
+    fn matched_cells_keep_their_style_when_the_list_clips_names() {
+        use crate::models::CacheData;
+        use ratatui::{backend::TestBackend, Terminal};
+        use std::collections::HashMap;
+
+        let mut app = app_searching(SearchMode::Substring, "vis");
+        let names = ["VisualStudio", "VisualStudioCode"];
+        app.load_data(CacheData {
+            templates: names.iter().map(|t| t.to_string()).collect(),
+            contents: HashMap::new(),
+            last_used: HashMap::new(),
+            slugs: HashMap::new(),
+            oversized: Vec::new(),
+        });
+        app.is_loading = false;
+        app.apply_filter();
+
+        let mut terminal = Terminal::new(TestBackend::new(12, 5)).unwrap();
+        terminal.draw(|f| draw_list_pane(f, &mut app, f.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+
+        // Inside the border each row is the highlight symbol, the "[ ] " marker, then the name.
+        let name_x = 1 + app.symbols.highlight.width() as u16 + 4;
+        let last_x = 10;
+        for (row, name) in names.iter().enumerate() {
+            let y = 1 + row as u16;
+            let row_text: String = (name_x..=last_x).map(|x| buffer[(x, y)].symbol()).collect();
+            assert!(name.starts_with(&row_text) && row_text.len() < name.len(), "{row_text:?} is not clipped");
+
+            let underlined: String = (name_x..=last_x)
+                .filter(|&x| buffer[(x, y)].modifier.contains(Modifier::UNDERLINED))
+                .map(|x| buffer[(x, y)].symbol())
+                .collect();
+            assert_eq!(underlined, "Vis", "row {row}");
+        }
+        // The unhighlighted row keeps the match color too (the highlighted one takes the cursor's colors).
+        for x in name_x..name_x + 3 {
+            assert_eq!(buffer[(x, 2)].fg, Color::Yellow);
+        }
+        assert_ne!(buffer[(name_x + 3, 2)].fg, Color::Yellow);
+    }
+
+    #[test]
+    fn no_query_leaves_the_name_plain() {
+        let app = app_searching(SearchMode::Fuzzy, "");
+        assert_eq!(marked(&highlight_matches(&app, "Rust")), "Rust");
+    }
+
+    #[test]
+    fn truncate_counts_display_columns() {
+        assert_eq!(truncate_to_width("Rust", 4, "…"), "Rust");
+        assert_eq!(truncate_to_width("JetBrains", 5, "…"), "JetB…");
+        // Double-width characters are never split: "日本" is 4 columns, so only "日" fits before the ellipsis.
+        assert_eq!(truncate_to_width("日本語", 4, "…"), "日…");
+        assert_eq!(truncate_to_width("🦀🦀🦀", 5, "…"), "🦀🦀…");
+        assert_eq!(truncate_to_width("Café crème", 6, "~"), "Café ~");
+        assert_eq!(truncate_to_width("Rust", 0, "…"), "");
+    }
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn wrap_names_fills_rows_then_counts_the_rest() {
+        let list = names(&["Go", "Rust", "Node", "Python", "Java"]);
+        assert_eq!(wrap_names(&list, 10, 10, 3, "~"), ["Go, Rust,", "Node,", "+2 more"]);
+        assert_eq!(wrap_names(&list, 30, 30, 1, "~"), ["Go, Rust, Node, Python, Java"]);
+    }
+
+    #[test]
+    fn every_row_fits_before_the_suffix_is_added() {
+        let list = names(&["VisualStudioCode", "JetBrains", "Rust", "Go", "Node"]);
+        let rows = wrap_names(&list, 8, 12, 2, "~");
+        for (i, row) in rows.iter().enumerate() {
+            assert!(row.width() <= if i == 0 { 8 } else { 12 }, "{rows:?}");
+        }
+        assert!(rows.last().unwrap().ends_with("more"), "{rows:?}");
+    }
+}
+